@@ -1,7 +1,17 @@
+use std::marker::PhantomData;
+
 use super::*;
 
-use events::{EntitySpawned, EntityDespawned};
-use types::EntityPrefab;
+use manufacture_engine::ECS::entity::EntityBuilder;
+use manufacture_engine::ECS::request::SystemData;
+use comp::{Parent, Children, Persistent, SceneMember, Tags};
+use events::{CommandFailed, EntitySpawned, EntityDespawned, ComponentAdded, ComponentRemoved, SceneLoaded, SceneUnloaded, TrackedComponent};
+use resources::{CloneRegistry, CommandHistory, ComponentTracker, DespawnSnapshots, PrefabRegistry, SceneRegistry, TagIndex};
+use storage::{ChangeLog, StorageChange};
+use types::{CommandError, ComponentSnapshot, EntityPrefab, SpawnHandle};
+use comp::{Tag, TagId};
+#[cfg(feature = "serde")]
+use types::WorldSnapshot;
 
 /// Send a Command to spawn a new Entity
 /// 
@@ -12,7 +22,7 @@ use types::EntityPrefab;
 pub struct Spawn;
 impl Command for Spawn{
     fn execute(&mut self, world: &mut World) {
-        let token = world.spawn().get_token();
+        let token = spawn_entity(world).get_token();
         world.get_event_writer::<EntitySpawned>().send(EntitySpawned(token));
     }
 }
@@ -20,17 +30,172 @@ impl Command for Spawn{
 /// Send a Command to spawn a new Entity with Components
 /// 
 /// Creates a new Entity using the Prefab's instructions and sends an Event with the Entity's Token
-pub struct SpawnPrefab<T: EntityPrefab>(T);
+pub struct SpawnPrefab<T: EntityPrefab>(pub T);
 impl<T: EntityPrefab + 'static> Command for SpawnPrefab<T>{
     fn execute(&mut self, world: &mut World) {
-        let builder = world.spawn();
+        let builder = spawn_entity(world);
         let token = builder.get_token(); // Quickly yoink it because the Prefab will consume the builder
 
         T::spawn(&self.0, builder);
-        world.get_event_writer::<EntitySpawned>().send(EntitySpawned(token));
+        spawned(world, token);
+    }
+}
+
+/// Send a Command that refers to an Entity spawned by an earlier Command
+/// 
+/// No Token exists before the spawning Command runs, so the handle is only read when this Command runs:
+/// the closure then builds the actual Command with the Entity's Token and it gets run right away.
+/// Commands run in the order they're sent, so send this after the spawning Command, even within the same frame:
+/// ```ignore
+/// let bullet = SpawnHandle::new();
+/// commands.send(SpawnPrefabWith::new(Bullet).fill_handle(&bullet));
+/// commands.send(AfterSpawn::new(&bullet, move |token| AttachChild{parent: gun, child: token}));
+/// ```
+/// Fails if the handle is still empty when this runs
+pub struct AfterSpawn<C: Command, F: FnOnce(Token) -> C>{
+    handle: SpawnHandle,
+    build: Option<F>
+}
+impl<C: Command, F: FnOnce(Token) -> C> AfterSpawn<C, F>{
+    pub fn new(handle: &SpawnHandle, build: F) -> Self{
+        Self{
+            handle: handle.clone(),
+            build: Some(build),
+        }
+    }
+}
+impl<C: Command, F: FnOnce(Token) -> C + 'static> Command for AfterSpawn<C, F>{
+    fn execute(&mut self, world: &mut World) {
+        let token = match self.handle.get(){
+            Some(token) => token,
+            None => return report::<Self>(world, CommandError::UnresolvedHandle),
+        };
+        if let Some(build) = self.build.take(){
+            build(token).execute(world);
+        }
     }
 }
 
+/// A change applied to an Entity after it's Prefab has spawned it
+type SpawnOverride = Box<dyn FnOnce(&mut World, Token) + Send>;
+
+/// Send a Command to spawn a new Entity with Components, then adjust it
+/// 
+/// Works like `SpawnPrefab`, but once the Prefab is done, Components given with `with` are put on the Entity,
+/// replacing the ones the Prefab made, and closures given with `then` are run in order
+/// 
+/// Use `handle` to get the spawned Entity's Token once the Command runs:
+/// ```ignore
+/// let mut cmd = SpawnPrefabWith::new(Orc).with(Transform2D{loc, rot: 0.0, scale});
+/// let handle = cmd.handle();
+/// commands.send(cmd);
+/// ```
+pub struct SpawnPrefabWith<T: EntityPrefab>{
+    prefab: T,
+    overrides: Vec<SpawnOverride>,
+    handle: Option<SpawnHandle>
+}
+impl<T: EntityPrefab> SpawnPrefabWith<T>{
+    pub fn new(prefab: T) -> Self{
+        Self{
+            prefab,
+            overrides: Vec::new(),
+            handle: None,
+        }
+    }
+    /// Put a Component on the spawned Entity, replacing the Prefab's one
+    pub fn with<C: Component + Send>(mut self, comp: C) -> Self{
+        self.overrides.push(Box::new(move |world, token| {
+            let mut storage = world.fetch_mut::<C>();
+            // Not all Storages overwrite on insert, so we clear the slot first
+            storage.remove(&token.id());
+            storage.insert(token.id(), comp);
+        }));
+        self
+    }
+    /// Run a closure on the spawned Entity
+    pub fn then(mut self, func: impl FnOnce(&mut World, Token) + Send + 'static) -> Self{
+        self.overrides.push(Box::new(func));
+        self
+    }
+    /// Get a handle that receives the spawned Entity's Token
+    pub fn handle(&mut self) -> SpawnHandle{
+        self.handle.get_or_insert_with(SpawnHandle::new).clone()
+    }
+    /// Put the spawned Entity's Token into a handle made beforehand
+    pub fn fill_handle(mut self, handle: &SpawnHandle) -> Self{
+        self.handle = Some(handle.clone());
+        self
+    }
+}
+impl<T: EntityPrefab + 'static> Command for SpawnPrefabWith<T>{
+    fn execute(&mut self, world: &mut World) {
+        let builder = spawn_entity(world);
+        let token = builder.get_token();
+
+        self.prefab.spawn(builder);
+        for func in self.overrides.drain(..){
+            func(world, token);
+        }
+
+        if let Some(handle) = &self.handle{
+            handle.set(token);
+        }
+        spawned(world, token);
+    }
+}
+
+/// Send a Command to spawn a new Entity from a Prefab registered in `PrefabRegistry`
+/// 
+/// Creates a new Entity using the Prefab's instructions and sends an Event with the Entity's Token
+/// 
+/// Fails if there's no Prefab with the given ID
+pub struct SpawnPrefabById(pub String);
+impl SpawnPrefabById{
+    pub fn new(id: impl Into<String>) -> Self{
+        Self(id.into())
+    }
+}
+impl Command for SpawnPrefabById{
+    fn execute(&mut self, world: &mut World) {
+        let spawner = match world.fetch_res::<PrefabRegistry>().get(&self.0){
+            Some(spawner) => spawner,
+            None => return report::<Self>(world, CommandError::MissingPrefab(self.0.clone())),
+        };
+        let builder = spawn_entity(world);
+        let token = builder.get_token();
+
+        spawner(builder);
+        spawned(world, token);
+    }
+}
+
+/// Send a Command to spawn a copy of an Entity
+/// 
+/// Copies every Component registered in `CloneRegistry` Resource and sends an Event with the new Entity's Token
+/// 
+/// Fails if the source Entity is gone
+pub struct CloneEntity(pub Token);
+impl Command for CloneEntity{
+    fn execute(&mut self, world: &mut World) {
+        if let Err(error) = clone_entity(world, self.0){
+            report::<Self>(world, error);
+        }
+    }
+}
+
+/// Spawns a copy of an Entity and sends the `EntitySpawned` Event
+fn clone_entity(world: &mut World, source: Token) -> Result<Token, CommandError>{
+    if !world.validate_token(&source){
+        return Err(CommandError::StaleToken(source))
+    }
+    let token = spawn_entity(world).get_token();
+    world.fetch_res::<CloneRegistry>().clone_components(world, source.id(), token.id());
+
+    spawned(world, token);
+    Ok(token)
+}
+
 /// Send a Command to despawn an Entity via ID
 /// 
 /// It's generally discouraged to despawn Entities this way.
@@ -38,8 +203,12 @@ impl<T: EntityPrefab + 'static> Command for SpawnPrefab<T>{
 pub struct DespawnID(pub usize);
 impl Command for DespawnID{
     fn execute(&mut self, world: &mut World) {
-        if world.despawn(self.0){
-            world.get_event_writer::<EntityDespawned>().send(EntityDespawned(self.0));
+        let result = match world.get_token(self.0){
+            Some(token) => despawn(world, token),
+            None => Err(CommandError::StaleID(self.0)),
+        };
+        if let Err(error) = result{
+            report::<Self>(world, error);
         }
     }
 }
@@ -50,8 +219,664 @@ impl Command for DespawnID{
 pub struct DespawnToken(pub Token);
 impl Command for DespawnToken{
     fn execute(&mut self, world: &mut World) {
-        if world.despawn_with_token(self.0){
-            world.get_event_writer::<EntityDespawned>().send(EntityDespawned(self.0.id()));
+        if let Err(error) = despawn(world, self.0){
+            report::<Self>(world, error);
         }
     }
+}
+
+/// Spawns a new Entity, announcing pending Storage changes first
+/// 
+/// Changes left behind by Entities despawned outside of Commands would otherwise get mixed up with the new Entity if it reuses their ID
+pub(crate) fn spawn_entity(world: &mut World) -> EntityBuilder<'_>{
+    world.fetch_res::<ComponentTracker>().announce(world);
+    world.spawn()
+}
+
+/// Sends the `EntitySpawned` Event for the freshly spawned Entity,
+/// followed by `ComponentAdded` for each of it's Components listed in `ComponentTracker`
+pub(crate) fn spawned(world: &World, token: Token){
+    world.get_event_writer::<EntitySpawned>().send(EntitySpawned(token));
+    world.fetch_res::<ComponentTracker>().announce(world);
+}
+
+/// Sends Events for every change made to the Component's Storage since it was last announced
+/// 
+/// Runs `TrackedComponent::changed` for each changed Entity first, Entities that have despawned since only get that
+pub(crate) fn announce<C: TrackedComponent>(world: &World){
+    let changes = world.fetch_mut::<C>().take_changes();
+    for (id, change) in changes{
+        C::changed(world, id);
+        let token = match world.get_token(id){
+            Some(token) => token,
+            None => continue,
+        };
+        match change{
+            StorageChange::Added => world.get_event_writer::<ComponentAdded<C>>().send(ComponentAdded::new(token)),
+            StorageChange::Removed => world.get_event_writer::<ComponentRemoved<C>>().send(ComponentRemoved::new(token)),
+            StorageChange::Replaced => {}
+        }
+    }
+}
+
+/// Captures the Entity's Components, despawns it and sends the `EntityDespawned` Event
+/// 
+/// `ComponentRemoved` is sent first for each of it's Components listed in `ComponentTracker`
+pub(crate) fn despawn(world: &mut World, token: Token) -> Result<(), CommandError>{
+    if !world.validate_token(&token){
+        return Err(CommandError::StaleToken(token))
+    }
+    let snapshot = world.fetch_res::<DespawnSnapshots>().capture(world, token.id());
+
+    // Clean up the hierarchy so no one links to a dead Entity
+    unlink_parent(world, token);
+    let children: Vec<Token> = world.fetch::<Children>().get(&token.id())
+        .map(|children| children.iter().copied().collect())
+        .unwrap_or_default();
+    for child in children{
+        unlink_parent(world, child);
+    }
+    // Removed while the Token is still valid, so the Events can carry it
+    {
+        let tracker = world.fetch_res::<ComponentTracker>();
+        tracker.strip(world, token.id());
+        tracker.announce(world);
+    }
+
+    if !world.despawn_with_token(token){
+        return Err(CommandError::StaleToken(token))
+    }
+    world.get_event_writer::<EntityDespawned>().send(EntityDespawned(token, snapshot));
+    Ok(())
+}
+
+/// Send a Command to add a Component to an Entity
+/// 
+/// Sends a `ComponentAdded` Event if the Entity didn't have the Component before.
+/// If it did, the Component gets replaced instead
+pub struct InsertComponent<C: TrackedComponent>(Token, Option<C>);
+impl<C: TrackedComponent> InsertComponent<C>{
+    pub fn new(token: Token, comp: C) -> Self{
+        Self(token, Some(comp))
+    }
+}
+impl<C: TrackedComponent> Command for InsertComponent<C>{
+    fn execute(&mut self, world: &mut World) {
+        if !world.validate_token(&self.0){
+            return report::<Self>(world, CommandError::StaleToken(self.0))
+        }
+        let comp = match self.1.take(){
+            Some(comp) => comp,
+            None => return,
+        };
+        let id = self.0.id();
+
+        {
+            let mut storage = world.fetch_mut::<C>();
+            // Not all Storages overwrite on insert, so we clear the slot first
+            storage.remove(&id);
+            storage.insert(id, comp);
+        }
+        announce::<C>(world);
+    }
+}
+
+/// Send a Command to remove a Component from an Entity
+/// 
+/// Sends a `ComponentRemoved` Event, fails if the Entity doesn't have the Component
+pub struct RemoveComponent<C: TrackedComponent>(pub Token, PhantomData<C>);
+impl<C: TrackedComponent> RemoveComponent<C>{
+    pub fn new(token: Token) -> Self{
+        Self(token, PhantomData)
+    }
+}
+impl<C: TrackedComponent> Command for RemoveComponent<C>{
+    fn execute(&mut self, world: &mut World) {
+        if !world.validate_token(&self.0){
+            return report::<Self>(world, CommandError::StaleToken(self.0))
+        }
+        let id = self.0.id();
+
+        let had_comp = {
+            let mut storage = world.fetch_mut::<C>();
+            let had_comp = storage.get(&id).is_some();
+            storage.remove(&id);
+            had_comp
+        };
+        if !had_comp{
+            return report::<Self>(world, CommandError::MissingComponent(self.0, C::ID))
+        }
+        announce::<C>(world);
+    }
+}
+
+/// Send a Command to tag an Entity
+/// 
+/// Gives the Entity a `Tags` Component if it doesn't have one yet, sending a `ComponentAdded` Event.
+/// Keeps `TagIndex` Resource in sync
+pub struct AddTag(pub Token, pub TagId);
+impl AddTag{
+    pub fn new<T: Tag>(token: Token) -> Self{
+        Self(token, TagId::of::<T>())
+    }
+}
+impl Command for AddTag{
+    fn execute(&mut self, world: &mut World) {
+        if !world.validate_token(&self.0){
+            return report::<Self>(world, CommandError::StaleToken(self.0))
+        }
+        let id = self.0.id();
+
+        {
+            let mut storage = world.fetch_mut::<Tags>();
+            match storage.get_mut(&id){
+                Some(tags) => {
+                    tags.insert(self.1);
+                }
+                None => {
+                    let mut tags = Tags::new();
+                    tags.insert(self.1);
+                    storage.insert(id, tags);
+                }
+            }
+        }
+        world.fetch_res_mut::<TagIndex>().insert(id, self.1);
+        announce::<Tags>(world);
+    }
+}
+
+/// Send a Command to remove a tag from an Entity
+/// 
+/// The `Tags` Component stays even if it ends up empty.
+/// Keeps `TagIndex` Resource in sync
+pub struct RemoveTag(pub Token, pub TagId);
+impl RemoveTag{
+    pub fn new<T: Tag>(token: Token) -> Self{
+        Self(token, TagId::of::<T>())
+    }
+}
+impl Command for RemoveTag{
+    fn execute(&mut self, world: &mut World) {
+        if !world.validate_token(&self.0){
+            return report::<Self>(world, CommandError::StaleToken(self.0))
+        }
+        let id = self.0.id();
+
+        if let Some(tags) = world.fetch_mut::<Tags>().get_mut(&id){
+            tags.remove(self.1);
+        }
+        world.fetch_res_mut::<TagIndex>().remove(id, self.1);
+    }
+}
+
+/// Send a Command to attach an Entity as a child of another Entity
+/// 
+/// If the child already has a parent, it gets detached from it first
+/// 
+/// Fails if either Entity is gone or if the link would make a cycle
+pub struct AttachChild{
+    pub parent: Token,
+    pub child: Token
+}
+impl Command for AttachChild{
+    fn execute(&mut self, world: &mut World) {
+        if let Err(error) = attach(world, self.parent, self.child){
+            report::<Self>(world, error);
+        }
+    }
+}
+
+/// Send a Command to detach an Entity from it's parent
+/// 
+/// The Entity becomes a root of it's own hierarchy
+pub struct DetachChild(pub Token);
+impl Command for DetachChild{
+    fn execute(&mut self, world: &mut World) {
+        if !world.validate_token(&self.0){
+            return report::<Self>(world, CommandError::StaleToken(self.0))
+        }
+        unlink_parent(world, self.0);
+    }
+}
+
+/// Send a Command to despawn an Entity along with all of it's descendants
+/// 
+/// Sends `EntityDespawned` Event for every removed Entity, children first
+pub struct DespawnRecursive(pub Token);
+impl Command for DespawnRecursive{
+    fn execute(&mut self, world: &mut World) {
+        if !world.validate_token(&self.0){
+            return report::<Self>(world, CommandError::StaleToken(self.0))
+        }
+        // Gather the whole tree first, parents before children
+        let mut tree = vec![self.0];
+        let mut index = 0;
+        while index < tree.len(){
+            if let Some(children) = world.fetch::<Children>().get(&tree[index].id()){
+                tree.extend(children.iter().copied());
+            }
+            index += 1;
+        }
+
+        for token in tree.into_iter().rev(){
+            if let Err(error) = despawn(world, token){
+                report::<Self>(world, error);
+            }
+        }
+    }
+}
+
+/// Links an Entity to a new parent, on both sides
+fn attach(world: &mut World, parent: Token, child: Token) -> Result<(), CommandError>{
+    for token in [parent, child]{
+        if !world.validate_token(&token){
+            return Err(CommandError::StaleToken(token))
+        }
+    }
+    // An Entity cannot be it's own ancestor
+    let mut ancestor = Some(parent);
+    while let Some(current) = ancestor{
+        if current == child{
+            return Err(CommandError::HierarchyCycle{parent, child})
+        }
+        ancestor = world.fetch::<Parent>().get(&current.id()).map(|parent| parent.0);
+    }
+
+    unlink_parent(world, child);
+
+    let had_children = world.fetch::<Children>().get(&parent.id()).is_some();
+    if !had_children{
+        world.fetch_mut::<Children>().insert(parent.id(), Children::new());
+        announce::<Children>(world);
+    }
+    if let Some(children) = world.fetch_mut::<Children>().get_mut(&parent.id()){
+        children.push(child);
+    }
+
+    world.fetch_mut::<Parent>().insert(child.id(), Parent(parent));
+    announce::<Parent>(world);
+    Ok(())
+}
+
+/// Removes the link between an Entity and it's parent, on both sides
+fn unlink_parent(world: &mut World, child: Token){
+    let parent = match world.fetch::<Parent>().get(&child.id()){
+        Some(parent) => parent.0,
+        None => return,
+    };
+    world.fetch_mut::<Parent>().remove(&child.id());
+    announce::<Parent>(world);
+
+    let now_empty = match world.fetch_mut::<Children>().get_mut(&parent.id()){
+        Some(children) => {
+            children.remove(&child);
+            children.is_empty()
+        }
+        None => false,
+    };
+    if now_empty{
+        world.fetch_mut::<Children>().remove(&parent.id());
+        announce::<Children>(world);
+    }
+}
+
+/// Send a Command to spawn a Scene registered in `SceneRegistry`
+/// 
+/// Every spawned Entity gets a `SceneMember` Component and an `EntitySpawned` Event,
+/// `SceneLoaded` Event is sent once they're all in
+/// 
+/// Fails if there's no Scene with the given ID, or it's already loaded
+pub struct LoadScene(pub String);
+impl LoadScene{
+    pub fn new(id: impl Into<String>) -> Self{
+        Self(id.into())
+    }
+}
+impl Command for LoadScene{
+    fn execute(&mut self, world: &mut World) {
+        if let Err(error) = load_scene(world, &self.0){
+            report::<Self>(world, error);
+        }
+    }
+}
+
+/// Send a Command to despawn every Entity of a Scene
+/// 
+/// Sends `EntityDespawned` Event for every removed Entity, then `SceneUnloaded` Event.
+/// `Persistent` Entities are kept, they just stop being members of the Scene
+pub struct UnloadScene(pub String);
+impl UnloadScene{
+    pub fn new(id: impl Into<String>) -> Self{
+        Self(id.into())
+    }
+}
+impl Command for UnloadScene{
+    fn execute(&mut self, world: &mut World) {
+        let id = self.0.as_str();
+        unload_scenes::<Self>(world, |scene| scene == id);
+    }
+}
+
+/// Send a Command to replace every loaded Scene with another one
+/// 
+/// Unloads all Scenes like `UnloadScene` does, including Entities of Scenes restored from a `WorldSnapshot`,
+/// then loads the new Scene like `LoadScene` does.
+/// Switching to an already loaded Scene reloads it from scratch
+/// 
+/// Fails without unloading anything if there's no Scene with the given ID
+pub struct SwitchScene(pub String);
+impl SwitchScene{
+    pub fn new(id: impl Into<String>) -> Self{
+        Self(id.into())
+    }
+}
+impl Command for SwitchScene{
+    fn execute(&mut self, world: &mut World) {
+        if !world.fetch_res::<SceneRegistry>().contains(&self.0){
+            return report::<Self>(world, CommandError::MissingScene(self.0.clone()))
+        }
+        unload_scenes::<Self>(world, |_| true);
+        if let Err(error) = load_scene(world, &self.0){
+            report::<Self>(world, error);
+        }
+    }
+}
+
+/// Spawns every Entity of a Scene and marks them as it's members
+fn load_scene(world: &mut World, id: &str) -> Result<(), CommandError>{
+    let scene = {
+        let scenes = world.fetch_res::<SceneRegistry>();
+        if scenes.is_loaded(id){
+            return Err(CommandError::SceneAlreadyLoaded(id.to_string()))
+        }
+        scenes.get(id).cloned().ok_or_else(|| CommandError::MissingScene(id.to_string()))?
+    };
+
+    for spawner in scene.iter(){
+        let builder = spawn_entity(world);
+        let token = builder.get_token();
+        spawner(builder);
+
+        world.fetch_mut::<SceneMember>().insert(token.id(), SceneMember(id.to_string()));
+        spawned(world, token);
+    }
+
+    world.fetch_res_mut::<SceneRegistry>().set_loaded(id);
+    world.get_event_writer::<SceneLoaded>().send(SceneLoaded(id.to_string()));
+    Ok(())
+}
+
+/// Despawns members of every Scene that passes `unload`, `Persistent` ones only lose their membership
+/// 
+/// Sends `SceneUnloaded` for every such Scene that was loaded or still had members
+fn unload_scenes<C: ?Sized>(world: &mut World, unload: impl Fn(&str) -> bool){
+    let members: Vec<(usize, String)> = {
+        let mut query = <Query<'_, (&SceneMember,)> as SystemData>::fetch(world);
+        query.iter()
+            .filter(|(_, (member,))| unload(&member.0))
+            .map(|(id, (member,))| (id, member.0.clone()))
+            .collect()
+    };
+    let mut scenes: Vec<String> = world.fetch_res::<SceneRegistry>().loaded()
+        .filter(|scene| unload(scene))
+        .map(|scene| scene.to_string())
+        .collect();
+
+    for (id, scene) in members{
+        if !scenes.contains(&scene){
+            scenes.push(scene);
+        }
+        if world.fetch::<Persistent>().get(&id).is_some(){
+            world.fetch_mut::<SceneMember>().remove(&id);
+            announce::<SceneMember>(world);
+            continue
+        }
+        let result = match world.get_token(id){
+            Some(token) => despawn(world, token),
+            None => Err(CommandError::StaleID(id)),
+        };
+        if let Err(error) = result{
+            report::<C>(world, error);
+        }
+    }
+
+    for scene in scenes{
+        world.fetch_res_mut::<SceneRegistry>().set_unloaded(&scene);
+        world.get_event_writer::<SceneUnloaded>().send(SceneUnloaded(scene));
+    }
+}
+
+/// Send a Command to save the World into a file
+/// 
+/// Saves everything registered in `SnapshotRegistry` Resource as JSON, see `WorldSnapshot`
+#[cfg(feature = "serde")]
+pub struct SaveWorld(pub std::path::PathBuf);
+#[cfg(feature = "serde")]
+impl Command for SaveWorld{
+    fn execute(&mut self, world: &mut World) {
+        if let Err(error) = WorldSnapshot::capture(world).and_then(|snapshot| snapshot.save_file(&self.0)){
+            report::<Self>(world, CommandError::Custom(error.to_string()));
+        }
+    }
+}
+
+/// Send a Command to load a World saved with `SaveWorld`
+/// 
+/// Saved Entities are spawned next to the existing ones, see `WorldSnapshot::restore`.
+/// If anything in the file is wrong, nothing gets loaded
+#[cfg(feature = "serde")]
+pub struct LoadWorld(pub std::path::PathBuf);
+#[cfg(feature = "serde")]
+impl Command for LoadWorld{
+    fn execute(&mut self, world: &mut World) {
+        if let Err(error) = WorldSnapshot::load_file(&self.0).and_then(|snapshot| snapshot.restore(world)){
+            report::<Self>(world, CommandError::Custom(error.to_string()));
+        }
+    }
+}
+
+/// Send a `CommandFailed` Event on behalf of Command `C`
+/// 
+/// Use it in your own Commands to report what went wrong:
+/// ```ignore
+/// return report::<Self>(world, CommandError::Custom(format!("{} has no ammo", self.0.id())))
+/// ```
+pub fn report<C: ?Sized>(world: &World, error: CommandError){
+    world.get_event_writer::<CommandFailed>().send(CommandFailed(std::any::type_name::<C>(), error));
+}
+
+/// # Reversible Command trait
+/// A Command that can be undone
+/// 
+/// `apply` runs the Command and returns another one that reverses it, or `None` if nothing happened.
+/// Send it wrapped in `Record` so that the reverse ends up in `CommandHistory`
+/// 
+/// Undoing a despawn spawns the Entity back with a new Token,
+/// so reverse Commands should refer to Entities through `CommandHistory::handle` instead of Tokens
+pub trait ReversibleCommand: 'static{
+    fn apply(&mut self, world: &mut World) -> Option<Box<dyn ReversibleCommand>>;
+}
+
+/// Send a Command that can be undone later
+/// 
+/// Runs the wrapped Command and stores it's reverse in `CommandHistory`
+pub struct Record<C: ReversibleCommand>(pub C);
+impl<C: ReversibleCommand> Command for Record<C>{
+    fn execute(&mut self, world: &mut World) {
+        if let Some(inverse) = self.0.apply(world){
+            world.fetch_res_mut::<CommandHistory>().record(inverse);
+        }
+    }
+}
+
+/// Send a Command to start grouping recorded Commands into a single undo step
+pub struct BeginTransaction(pub String);
+impl Command for BeginTransaction{
+    fn execute(&mut self, world: &mut World) {
+        world.fetch_res_mut::<CommandHistory>().begin(std::mem::take(&mut self.0));
+    }
+}
+
+/// Send a Command to stop grouping recorded Commands
+pub struct EndTransaction;
+impl Command for EndTransaction{
+    fn execute(&mut self, world: &mut World) {
+        world.fetch_res_mut::<CommandHistory>().end();
+    }
+}
+
+/// Send a Command to undo the last step in `CommandHistory`
+pub struct Undo;
+impl Command for Undo{
+    fn execute(&mut self, world: &mut World) {
+        let transaction = match world.fetch_res_mut::<CommandHistory>().pop_undo(){
+            Some(transaction) => transaction,
+            None => return,
+        };
+        let reverse = transaction.run(world);
+        world.fetch_res_mut::<CommandHistory>().push_redo(reverse);
+    }
+}
+
+/// Send a Command to redo the last undone step in `CommandHistory`
+pub struct Redo;
+impl Command for Redo{
+    fn execute(&mut self, world: &mut World) {
+        let transaction = match world.fetch_res_mut::<CommandHistory>().pop_redo(){
+            Some(transaction) => transaction,
+            None => return,
+        };
+        let reverse = transaction.run(world);
+        world.fetch_res_mut::<CommandHistory>().push_undo(reverse);
+    }
+}
+
+impl ReversibleCommand for Spawn{
+    fn apply(&mut self, world: &mut World) -> Option<Box<dyn ReversibleCommand>> {
+        let token = spawn_entity(world).get_token();
+        world.get_event_writer::<EntitySpawned>().send(EntitySpawned(token));
+
+        let handle = world.fetch_res_mut::<CommandHistory>().handle(token);
+        Some(Box::new(DespawnHandle(handle)))
+    }
+}
+
+/// Undoing this spawns the Entity back in it's old place in the hierarchy,
+/// with the Components registered in `CommandHistory`
+impl ReversibleCommand for DespawnToken{
+    fn apply(&mut self, world: &mut World) -> Option<Box<dyn ReversibleCommand>> {
+        if !world.validate_token(&self.0){
+            report::<Self>(world, CommandError::StaleToken(self.0));
+            return None
+        }
+        let handle = world.fetch_res_mut::<CommandHistory>().handle(self.0);
+        DespawnHandle(handle).apply(world)
+    }
+}
+
+impl<C: TrackedComponent + Clone> ReversibleCommand for InsertComponent<C>{
+    fn apply(&mut self, world: &mut World) -> Option<Box<dyn ReversibleCommand>> {
+        if !world.validate_token(&self.0){
+            report::<Self>(world, CommandError::StaleToken(self.0));
+            return None
+        }
+        let handle = world.fetch_res_mut::<CommandHistory>().handle(self.0);
+        InsertHandle(handle, self.1.take()).apply(world)
+    }
+}
+
+impl<C: TrackedComponent + Clone> ReversibleCommand for RemoveComponent<C>{
+    fn apply(&mut self, world: &mut World) -> Option<Box<dyn ReversibleCommand>> {
+        if !world.validate_token(&self.0){
+            report::<Self>(world, CommandError::StaleToken(self.0));
+            return None
+        }
+        let handle = world.fetch_res_mut::<CommandHistory>().handle(self.0);
+        RemoveHandle::<C>(handle, PhantomData).apply(world)
+    }
+}
+
+/// Despawns the Entity behind a history handle
+struct DespawnHandle(SpawnHandle);
+impl ReversibleCommand for DespawnHandle{
+    fn apply(&mut self, world: &mut World) -> Option<Box<dyn ReversibleCommand>> {
+        let token = self.0.get().filter(|token| world.validate_token(token))?;
+        let respawn = {
+            let mut history = world.fetch_res_mut::<CommandHistory>();
+            let parent = world.fetch::<Parent>().get(&token.id()).map(|parent| history.handle(parent.0));
+            let children = world.fetch::<Children>().get(&token.id())
+                .map(|children| children.iter().map(|child| history.handle(*child)).collect())
+                .unwrap_or_default();
+            RespawnHandle{
+                handle: self.0.clone(),
+                snapshot: history.capture(world, token.id()),
+                parent,
+                children,
+            }
+        };
+
+        despawn(world, token).ok()?;
+        Some(Box::new(respawn))
+    }
+}
+
+/// Spawns a despawned Entity back and points it's history handle to it
+/// 
+/// Links it back to it's parent and those of it's children that are still around and haven't got a new parent since
+struct RespawnHandle{
+    handle: SpawnHandle,
+    snapshot: ComponentSnapshot,
+    parent: Option<SpawnHandle>,
+    children: Vec<SpawnHandle>
+}
+impl ReversibleCommand for RespawnHandle{
+    fn apply(&mut self, world: &mut World) -> Option<Box<dyn ReversibleCommand>> {
+        let token = spawn_entity(world).get_token();
+        {
+            let mut history = world.fetch_res_mut::<CommandHistory>();
+            history.restore(world, token.id(), &mut self.snapshot);
+            history.rebind(&self.handle, token);
+        }
+        spawned(world, token);
+
+        // Restoring the hierarchy is best effort, it could've changed in ways that make the old links impossible
+        if let Some(parent) = self.parent.as_ref().and_then(|parent| parent.get()){
+            attach(world, parent, token).ok();
+        }
+        for child in self.children.iter().filter_map(|child| child.get()){
+            let orphan = world.validate_token(&child) && world.fetch::<Parent>().get(&child.id()).is_none();
+            if orphan{
+                attach(world, token, child).ok();
+            }
+        }
+        Some(Box::new(DespawnHandle(self.handle.clone())))
+    }
+}
+
+/// Inserts a Component on the Entity behind a history handle
+struct InsertHandle<C: TrackedComponent + Clone>(SpawnHandle, Option<C>);
+impl<C: TrackedComponent + Clone> ReversibleCommand for InsertHandle<C>{
+    fn apply(&mut self, world: &mut World) -> Option<Box<dyn ReversibleCommand>> {
+        let token = self.0.get().filter(|token| world.validate_token(token))?;
+        let comp = self.1.take()?;
+        let previous = world.fetch::<C>().get(&token.id()).cloned();
+
+        InsertComponent::new(token, comp).execute(world);
+        match previous{
+            Some(previous) => Some(Box::new(InsertHandle(self.0.clone(), Some(previous)))),
+            None => Some(Box::new(RemoveHandle::<C>(self.0.clone(), PhantomData))),
+        }
+    }
+}
+
+/// Removes a Component from the Entity behind a history handle
+struct RemoveHandle<C: TrackedComponent + Clone>(SpawnHandle, PhantomData<C>);
+impl<C: TrackedComponent + Clone> ReversibleCommand for RemoveHandle<C>{
+    fn apply(&mut self, world: &mut World) -> Option<Box<dyn ReversibleCommand>> {
+        let token = self.0.get().filter(|token| world.validate_token(token))?;
+        let previous = world.fetch::<C>().get(&token.id()).cloned()?;
+
+        RemoveComponent::<C>::new(token).execute(world);
+        Some(Box::new(InsertHandle(self.0.clone(), Some(previous))))
+    }
 }
\ No newline at end of file
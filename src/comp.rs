@@ -1,85 +1,429 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::ops::{Mul, MulAssign};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
 
 use super::*;
 use super::storage::*;
-use super::types::{Vector2, Vector3};
+use super::events::TrackedComponent;
+use super::resources::TagIndex;
+use super::types::{Affine2, Easing, Mat3, Mat4, Quat, Vector2, Vector3};
 
 /// 2D Transform Component
 /// 
 /// Holds XY position, head-on rotation in Radians and XY scale
+/// 
+/// Rotation is counter-clockwise, with +X being 0
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transform2D{
     pub loc: Vector2,
     pub rot: f32,
     pub scale: Vector2
 }
 impl Component for Transform2D{
-    type STORAGE = BTreeMapStorage<Self>;
+    type STORAGE = TrackedStorage<Self, BTreeMapStorage<Self>>;
 
     const ID: &'static str = "Transform2D";
 }
+impl TrackedComponent for Transform2D{
+    const ADDED_ID: &'static str = "Transform2DAdded";
+    const REMOVED_ID: &'static str = "Transform2DRemoved";
+}
+impl Transform2D{
+    /// Returns a Matrix that scales, then rotates, then translates
+    pub fn to_affine(&self) -> Affine2{
+        Affine2::from_trs(self.loc, self.rot, self.scale)
+    }
+    /// Transform a point from local space into the space `self` lives in
+    pub fn transform_point(&self, point: Vector2) -> Vector2{
+        self.loc + (point * self.scale).rotate(self.rot)
+    }
+    /// Transform a point back into local space, undoing `transform_point`
+    pub fn inverse_transform_point(&self, point: Vector2) -> Vector2{
+        (point - self.loc).rotate(-self.rot) / self.scale
+    }
+    /// Rotate by `angle` Radians around `pivot`, moving `loc` along
+    pub fn rotate_around(&mut self, pivot: Vector2, angle: f32){
+        self.loc = pivot + (self.loc - pivot).rotate(angle);
+        self.rot += angle;
+    }
+}
+/// Composes `parent * child` into the child's Transform in the parent's space
+/// 
+/// Note: Same as `Transform2DPropagator`, non-uniform parent scale is applied along the child's axes and doesn't skew it
+impl Mul for Transform2D{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self{
+            loc: self.transform_point(rhs.loc),
+            rot: self.rot + rhs.rot,
+            scale: self.scale * rhs.scale,
+        }
+    }
+}
+impl MulAssign for Transform2D{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
 
 /// 3D Transform Component
 /// 
 /// Holds position in all 3 axis, XYZ Euler rotation in Radians and scale in all 3 axis
 /// 
 /// Note: Z is up in this engine
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transform3D{
     pub loc: Vector3,
     pub rot: Vector3,
     pub scale: Vector3
 }
 impl Component for Transform3D{
-    type STORAGE = BTreeMapStorage<Self>;
+    type STORAGE = TrackedStorage<Self, BTreeMapStorage<Self>>;
 
     const ID: &'static str = "Transform3D";
 }
+impl TrackedComponent for Transform3D{
+    const ADDED_ID: &'static str = "Transform3DAdded";
+    const REMOVED_ID: &'static str = "Transform3DRemoved";
+}
+impl Transform3D{
+    /// Returns the rotation as a Matrix, see `Mat3::from_euler` for the order
+    pub fn rotation_matrix(&self) -> Mat3{
+        Mat3::from_euler(self.rot)
+    }
+    /// Returns the rotation as a Quaternion
+    pub fn rotation(&self) -> Quat{
+        Quat::from_euler(self.rot)
+    }
+    /// Returns a Matrix that scales, then rotates, then translates
+    pub fn to_matrix(&self) -> Mat4{
+        Mat4::from_parts(self.rotation_matrix() * Mat3::from_scale(self.scale), self.loc)
+    }
+    /// Transform a point from local space into the space `self` lives in
+    pub fn transform_point(&self, point: Vector3) -> Vector3{
+        self.loc + self.rotation_matrix() * (point * self.scale)
+    }
+    /// Transform a point back into local space, undoing `transform_point`
+    pub fn inverse_transform_point(&self, point: Vector3) -> Vector3{
+        (self.rotation_matrix().transpose() * (point - self.loc)) / self.scale
+    }
+    /// Returns the Transform undoing `self`
+    /// 
+    /// Note: With non-uniform scale on a rotated Transform the exact inverse would be skewed, which this can't hold.
+    /// Use `inverse_transform_point` or `to_matrix().inverse()` if you need it exact
+    pub fn inverse(&self) -> Self{
+        let inv_rot = self.rotation_matrix().transpose();
+        let inv_scale = Vector3::new(1.0 / self.scale.x, 1.0 / self.scale.y, 1.0 / self.scale.z);
+        Self{
+            loc: (inv_rot * self.loc) * inv_scale * -1.0,
+            rot: inv_rot.to_euler(),
+            scale: inv_scale
+        }
+    }
+    /// Rotate to face `target`
+    /// 
+    /// +Y is forward, +X is right and +Z is up. `up` is usually `Vector3::new(0.0, 0.0, 1.0)`.
+    /// Does nothing if `target` is at `loc` or straight along `up`
+    pub fn look_at(&mut self, target: Vector3, up: Vector3){
+        let forward = target - self.loc;
+        if forward.magnitude() < f32::EPSILON{
+            return
+        }
+        let forward = forward.normalize();
+        let right = forward.cross(up);
+        if right.magnitude() < f32::EPSILON{
+            return
+        }
+        let right = right.normalize();
+        self.rot = Mat3::from_cols(right, forward, right.cross(forward)).to_euler();
+    }
+}
+
+/// Global 2D Transform Component
+/// 
+/// World-space counterpart of `Transform2D`, with all parent transforms applied.
+/// Kept up to date by `Transform2DPropagator`, don't modify it yourself
+/// 
+/// Note: Non-uniform parent scale is applied along the child's axes, rotated children don't get skewed
+#[derive(Clone, Copy)]
+pub struct GlobalTransform2D{
+    pub loc: Vector2,
+    pub rot: f32,
+    pub scale: Vector2
+}
+impl GlobalTransform2D{
+    /// Transform that leaves everything where it is, used for Entities without a parent
+    pub const IDENTITY: Self = Self{
+        loc: Vector2{x: 0.0, y: 0.0},
+        rot: 0.0,
+        scale: Vector2{x: 1.0, y: 1.0},
+    };
+    /// Apply a local Transform on top of this one
+    pub fn then(self, local: &Transform2D) -> Self{
+        Self{
+            loc: self.loc + (local.loc * self.scale).rotate(self.rot),
+            rot: self.rot + local.rot,
+            scale: self.scale * local.scale,
+        }
+    }
+}
+impl Component for GlobalTransform2D{
+    type STORAGE = BTreeMapStorage<Self>;
+
+    const ID: &'static str = "GlobalTransform2D";
+}
+
+/// Global 3D Transform Component
+/// 
+/// World-space counterpart of `Transform3D`, with all parent transforms applied.
+/// Kept up to date by `Transform3DPropagator`, don't modify it yourself
+/// 
+/// Note: Non-uniform parent scale is applied along the child's axes, rotated children don't get skewed
+#[derive(Clone, Copy)]
+pub struct GlobalTransform3D{
+    pub loc: Vector3,
+    pub rot: Vector3,
+    pub scale: Vector3
+}
+impl GlobalTransform3D{
+    /// Transform that leaves everything where it is, used for Entities without a parent
+    pub const IDENTITY: Self = Self{
+        loc: Vector3{x: 0.0, y: 0.0, z: 0.0},
+        rot: Vector3{x: 0.0, y: 0.0, z: 0.0},
+        scale: Vector3{x: 1.0, y: 1.0, z: 1.0},
+    };
+    /// Apply a local Transform on top of this one
+    pub fn then(self, local: &Transform3D) -> Self{
+        let rot = Mat3::from_euler(self.rot);
+        Self{
+            loc: self.loc + rot * (local.loc * self.scale),
+            rot: (rot * Mat3::from_euler(local.rot)).to_euler(),
+            scale: self.scale * local.scale,
+        }
+    }
+}
+impl Component for GlobalTransform3D{
+    type STORAGE = BTreeMapStorage<Self>;
+
+    const ID: &'static str = "GlobalTransform3D";
+}
 
 /// Holds tags for a given Entity
+/// 
+/// Changing it directly marks it as changed, `WithTag` and `WithoutTag` look at it instead of `TagIndex` Resource
+/// until `TagIndexer` System brings the index up to date on the next frame.
+/// `AddTag` and `RemoveTag` Commands update the index right away
+#[derive(Clone)]
 pub struct Tags{
-    inner: HashSet<&'static str>
+    inner: HashSet<TagId>,
+    /// Changed since `TagIndex` last saw it
+    dirty: bool
 }
 impl Tags{
     pub fn new() -> Self{
         Self{
             inner: HashSet::new(),
+            dirty: false,
         }
     }
     /// Check if this Entity has a given tag
     pub fn has<T: Tag>(&self) -> bool{
-        self.inner.contains(T::ID)
+        self.inner.contains(&TagId::of::<T>())
     }
     /// Check if this Entity has a given tag via it's ID
     /// 
     /// Note: Because this method takes a tag ID it may not line up with the tag from another plugin you're using
-    pub fn has_id(&self, tag: &'static str) -> bool{
-        self.inner.contains(tag)
+    pub fn has_id(&self, tag: &str) -> bool{
+        TagId::get(tag).is_some_and(|tag| self.inner.contains(&tag))
+    }
+    /// Check if this Entity has a given interned tag
+    pub fn contains(&self, tag: TagId) -> bool{
+        self.inner.contains(&tag)
     }
     /// Tag this entity with a tag
     pub fn tag<T: Tag>(&mut self){
-        self.inner.insert(T::ID);
+        self.dirty |= self.inner.insert(TagId::of::<T>());
     }
     /// Tag this entity with a tag
     /// 
+    /// Works with tags made at runtime too, they get interned, see `TagId`
+    /// 
     /// Note: Because this method takes a tag ID it may not line up with the tag from another plugin you're using
-    pub fn tag_id(&mut self, tag: &'static str){
-        self.inner.insert(tag);
+    pub fn tag_id(&mut self, tag: &str){
+        self.dirty |= self.inner.insert(TagId::new(tag));
+    }
+    /// Tag this entity with an interned tag
+    /// 
+    /// Returns `false` if the Entity already had it
+    pub fn insert(&mut self, tag: TagId) -> bool{
+        let inserted = self.inner.insert(tag);
+        self.dirty |= inserted;
+        inserted
     }
     /// Remove the given tag from the Entity
     pub fn untag<T: Tag>(&mut self){
-        self.inner.remove(T::ID);
+        self.dirty |= self.inner.remove(&TagId::of::<T>());
     }
     /// Remove the given tag from the Entity
     /// 
     /// Note: Because this method takes a tag ID it may not line up with the tag from another plugin you're using
-    pub fn untag_id(&mut self, tag: &'static str){
-        self.inner.remove(tag);
+    pub fn untag_id(&mut self, tag: &str){
+        if let Some(tag) = TagId::get(tag){
+            self.dirty |= self.inner.remove(&tag);
+        }
+    }
+    /// Remove the given interned tag from the Entity
+    /// 
+    /// Returns `false` if the Entity didn't have it
+    pub fn remove(&mut self, tag: TagId) -> bool{
+        let removed = self.inner.remove(&tag);
+        self.dirty |= removed;
+        removed
+    }
+    /// Iterate over the Entity's tags, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = TagId> + '_{
+        self.inner.iter().copied()
+    }
+    pub fn len(&self) -> usize{
+        self.inner.len()
+    }
+    pub fn is_empty(&self) -> bool{
+        self.inner.is_empty()
+    }
+    /// Check if the tags changed since `TagIndex` was last brought up to date
+    pub fn is_dirty(&self) -> bool{
+        self.dirty
+    }
+    pub(crate) fn mark_clean(&mut self){
+        self.dirty = false
+    }
+}
+impl Default for Tags{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+/// Serialized as a list of tag names
+#[cfg(feature = "serde")]
+impl serde::Serialize for Tags{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Sorted so that the same Tags always serialize the same
+        let mut tags: Vec<Arc<str>> = self.inner.iter().map(|tag| tag.name()).collect();
+        tags.sort_unstable();
+        serializer.collect_seq(tags.iter().map(|tag| &**tag))
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Tags{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tags = Vec::<std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Ok(Self{
+            inner: tags.iter().map(|tag| TagId::new(tag)).collect(),
+            dirty: false,
+        })
     }
 }
 impl Component for Tags{
-    type STORAGE = HashMapStorage<Self>;
+    type STORAGE = TrackedStorage<Self>;
 
     const ID: &'static str = "Tags";
 }
+impl TrackedComponent for Tags{
+    const ADDED_ID: &'static str = "TagsAdded";
+    const REMOVED_ID: &'static str = "TagsRemoved";
+
+    /// Keeps `TagIndex` Resource in line with the Storage
+    fn changed(world: &World, id: usize){
+        world.fetch_res_mut::<TagIndex>().sync(id, world.fetch::<Tags>().get(&id));
+    }
+}
+
+/// # Tag ID
+/// A tag name interned into a small number
+/// 
+/// Every distinct name gets it's own ID the first time it's seen, the same name always maps to the same ID.
+/// Names are kept for the rest of the program, but only once, so tags made at runtime (`faction_3`)
+/// or loaded from files over and over again don't pile up
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct TagId(u32);
+impl TagId{
+    /// Get the ID of a tag name, interning it if it's new
+    pub fn new(name: &str) -> Self{
+        if let Some(tag) = Self::get(name){
+            return tag
+        }
+        let mut interner = tag_interner().write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Someone could've interned it between the locks
+        if let Some(tag) = interner.ids.get(name){
+            return *tag
+        }
+        let tag = TagId(interner.names.len() as u32);
+        let name: Arc<str> = Arc::from(name);
+        interner.names.push(name.clone());
+        interner.ids.insert(name, tag);
+        tag
+    }
+    /// Get the ID of a tag name, if it has been interned already
+    pub fn get(name: &str) -> Option<Self>{
+        tag_interner().read().unwrap_or_else(|poisoned| poisoned.into_inner()).ids.get(name).copied()
+    }
+    /// Get the ID of a `Tag`
+    pub fn of<T: Tag>() -> Self{
+        Self::new(T::ID)
+    }
+    /// Get the name this ID was interned from
+    pub fn name(self) -> Arc<str>{
+        tag_interner().read().unwrap_or_else(|poisoned| poisoned.into_inner()).names[self.0 as usize].clone()
+    }
+    /// Get the raw number behind this ID
+    /// 
+    /// Only meaningful within the same run of the program, save tags by their `name` instead
+    pub fn index(self) -> u32{
+        self.0
+    }
+}
+impl std::fmt::Display for TagId{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+impl From<&str> for TagId{
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+/// Serialized as the tag's name, as the number is different between runs
+#[cfg(feature = "serde")]
+impl serde::Serialize for TagId{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TagId{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = std::borrow::Cow::<'de, str>::deserialize(deserializer)?;
+        Ok(Self::new(&name))
+    }
+}
+
+/// Every tag name seen so far, `TagId` is an index into `names`
+struct TagInterner{
+    names: Vec<Arc<str>>,
+    ids: HashMap<Arc<str>, TagId>
+}
+fn tag_interner() -> &'static RwLock<TagInterner>{
+    static INTERNER: LazyLock<RwLock<TagInterner>> = LazyLock::new(|| RwLock::new(TagInterner{
+        names: Vec::new(),
+        ids: HashMap::new(),
+    }));
+    &INTERNER
+}
+
 /// Tag trait
 /// 
 /// Rudimentary trait for ease of use of `Tags` component
@@ -87,33 +431,327 @@ pub trait Tag{
     const ID: &'static str;
 }
 
+/// A single `CMDSprite` *"pixel"*: Symbol, Foreground RGB, Background RGB
+pub type CMDPixel = (char, (u8, u8, u8), (u8, u8, u8));
+
 /// A Command-Line sprite
 /// 
 /// Represents a 2D ASCII art image
 /// 
 /// Individual *"pixels"* are `(ch, fg, bg)` tuples: `ch`aracter, `f`ore`g`round color and `b`ack`g`round color.  
 /// FG and BG colors are `(R, G, B)` tuples that use `u8` as values
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CMDSprite{
     pub size_x: u8,
     pub size_y: u8,
     pub z_index: u16,
-    pub data: Vec<(char, (u8, u8, u8), (u8, u8, u8))> // Symbol, Foreground RGB, Background RGB
+    pub data: Vec<CMDPixel>
 }
 impl Component for CMDSprite{
-    type STORAGE = HashMapStorage<Self>;
+    type STORAGE = TrackedStorage<Self>;
 
     const ID: &'static str = "CMDSprite";
 }
+impl TrackedComponent for CMDSprite{
+    const ADDED_ID: &'static str = "CMDSpriteAdded";
+    const REMOVED_ID: &'static str = "CMDSpriteRemoved";
+}
 
 /// Identifies an Entity as being controlled by the player
 /// 
 /// Typically used to direct player actions to a specific entity
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlayerController{
     pub pid: u32,
     pub active: bool,
 }
 impl Component for PlayerController{
-    type STORAGE = VecStorage<Self>;
+    type STORAGE = TrackedStorage<Self, VecStorage<Self>>;
 
     const ID: &'static str = "PlayerController";
-}
\ No newline at end of file
+}
+impl TrackedComponent for PlayerController{
+    const ADDED_ID: &'static str = "PlayerControllerAdded";
+    const REMOVED_ID: &'static str = "PlayerControllerRemoved";
+}
+
+/// Links an Entity to it's parent Entity
+/// 
+/// Don't insert this yourself, use `AttachChild` and `DetachChild` Commands so that both sides of the link stay in sync
+pub struct Parent(pub Token);
+impl Component for Parent{
+    type STORAGE = TrackedStorage<Self>;
+
+    const ID: &'static str = "Parent";
+}
+impl TrackedComponent for Parent{
+    const ADDED_ID: &'static str = "ParentAdded";
+    const REMOVED_ID: &'static str = "ParentRemoved";
+}
+
+/// Holds Tokens of an Entity's children
+/// 
+/// Don't modify this yourself, use `AttachChild` and `DetachChild` Commands so that both sides of the link stay in sync
+pub struct Children{
+    inner: Vec<Token>
+}
+impl Children{
+    pub fn new() -> Self{
+        Self{
+            inner: Vec::new(),
+        }
+    }
+    /// Check if the given Entity is a child of this Entity
+    pub fn contains(&self, token: &Token) -> bool{
+        self.inner.contains(token)
+    }
+    /// Iterate over the children's Tokens
+    pub fn iter(&self) -> std::slice::Iter<'_, Token>{
+        self.inner.iter()
+    }
+    pub fn len(&self) -> usize{
+        self.inner.len()
+    }
+    pub fn is_empty(&self) -> bool{
+        self.inner.is_empty()
+    }
+    pub(crate) fn push(&mut self, token: Token){
+        if !self.inner.contains(&token){
+            self.inner.push(token);
+        }
+    }
+    pub(crate) fn remove(&mut self, token: &Token){
+        self.inner.retain(|child| child != token);
+    }
+}
+impl Default for Children{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Component for Children{
+    type STORAGE = TrackedStorage<Self>;
+
+    const ID: &'static str = "Children";
+}
+impl TrackedComponent for Children{
+    const ADDED_ID: &'static str = "ChildrenAdded";
+    const REMOVED_ID: &'static str = "ChildrenRemoved";
+}
+
+/// Marks the Scene an Entity was spawned by
+/// 
+/// Set by `LoadScene` and `SwitchScene` Commands, `UnloadScene` despawns every Entity marked with that Scene
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SceneMember(pub String);
+impl Component for SceneMember{
+    type STORAGE = TrackedStorage<Self>;
+
+    const ID: &'static str = "SceneMember";
+}
+impl TrackedComponent for SceneMember{
+    const ADDED_ID: &'static str = "SceneMemberAdded";
+    const REMOVED_ID: &'static str = "SceneMemberRemoved";
+}
+
+/// Keeps an Entity alive when it's Scene gets unloaded
+/// 
+/// The Entity stops being a `SceneMember` instead, so it also survives `SwitchScene`.
+/// Typically used for the player
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Persistent;
+impl Component for Persistent{
+    type STORAGE = TrackedStorage<Self>;
+
+    const ID: &'static str = "Persistent";
+}
+impl TrackedComponent for Persistent{
+    const ADDED_ID: &'static str = "PersistentAdded";
+    const REMOVED_ID: &'static str = "PersistentRemoved";
+}
+
+/// What a `Tween` animates, and between which values
+/// 
+/// Each variant writes to a field of another Component on the same Entity, Entities without that Component are left alone
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TweenTarget{
+    /// `Transform2D::loc`
+    Loc2D{from: Vector2, to: Vector2},
+    /// `Transform2D::rot`
+    Rot2D{from: f32, to: f32},
+    /// `Transform2D::scale`
+    Scale2D{from: Vector2, to: Vector2},
+    /// `Transform3D::loc`
+    Loc3D{from: Vector3, to: Vector3},
+    /// `Transform3D::rot`, goes through a Quaternion so it takes the shortest way around
+    Rot3D{from: Vector3, to: Vector3},
+    /// `Transform3D::scale`
+    Scale3D{from: Vector3, to: Vector3},
+    /// Foreground color of every `CMDSprite` pixel
+    Foreground{from: (u8, u8, u8), to: (u8, u8, u8)},
+    /// Background color of every `CMDSprite` pixel
+    Background{from: (u8, u8, u8), to: (u8, u8, u8)},
+}
+
+/// What a `Tween` does once it reaches the end
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TweenMode{
+    /// Stop at the end and remove the `Tween`
+    #[default]
+    Once,
+    /// Jump back to the start and go again
+    Loop,
+    /// Go back and forth between the ends
+    PingPong,
+}
+
+/// Where a `Tween` is at after being advanced
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TweenState{
+    Running,
+    /// Reached an end and went around, only for looping modes
+    Looped,
+    /// Reached the end, only for `TweenMode::Once`
+    Completed,
+}
+
+/// Animates a field of the Entity's `Transform2D`, `Transform3D` or `CMDSprite` over time
+/// 
+/// Driven by `TweenRunner`, which sends `TweenCompleted` and `TweenLooped` Events.
+/// An Entity can only have one `Tween`, insert a new one after `TweenCompleted` to chain them
+#[derive(Clone)]
+pub struct Tween{
+    pub target: TweenTarget,
+    pub duration: Duration,
+    pub easing: Easing,
+    pub mode: TweenMode,
+    elapsed: Duration,
+    reversed: bool,
+    finished: bool,
+}
+impl Tween{
+    /// Create a new linear `Tween` that plays once
+    pub fn new(target: TweenTarget, duration: Duration) -> Self{
+        Self{
+            target,
+            duration,
+            easing: Easing::Linear,
+            mode: TweenMode::Once,
+            elapsed: Duration::ZERO,
+            reversed: false,
+            finished: false,
+        }
+    }
+    pub fn with_easing(mut self, easing: Easing) -> Self{
+        self.easing = easing;
+        self
+    }
+    pub fn with_mode(mut self, mode: TweenMode) -> Self{
+        self.mode = mode;
+        self
+    }
+    /// Returns how far into the current cycle the `Tween` is, from 0 to 1
+    pub fn progress(&self) -> f32{
+        if self.duration.is_zero(){
+            return 1.0
+        }
+        (self.elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+    /// Returns `true` if a `TweenMode::PingPong` Tween is on it's way back
+    pub fn is_reversed(&self) -> bool{
+        self.reversed
+    }
+    pub fn is_finished(&self) -> bool{
+        self.finished
+    }
+    /// Jump back to the start
+    pub fn restart(&mut self){
+        self.elapsed = Duration::ZERO;
+        self.reversed = false;
+        self.finished = false;
+    }
+    /// Move the `Tween` forward by `delta`
+    pub fn advance(&mut self, delta: Duration) -> TweenState{
+        if self.finished{
+            return TweenState::Completed
+        }
+        self.elapsed += delta;
+        if self.elapsed < self.duration{
+            return TweenState::Running
+        }
+        match self.mode{
+            TweenMode::Once => {
+                self.elapsed = self.duration;
+                self.finished = true;
+                return TweenState::Completed
+            }
+            TweenMode::Loop => (),
+            TweenMode::PingPong => self.reversed = !self.reversed,
+        }
+        // Only keep the leftover so a long frame doesn't skip a cycle's Event
+        self.elapsed = match self.duration.is_zero(){
+            true => Duration::ZERO,
+            false => (self.elapsed - self.duration).min(self.duration),
+        };
+        TweenState::Looped
+    }
+    /// Returns the eased position between `from` and `to`, with direction taken into account
+    pub fn sample(&self) -> f32{
+        let t = self.progress();
+        self.easing.apply(if self.reversed {1.0 - t} else {t})
+    }
+    /// Write the current value to a `Transform2D`
+    pub fn apply_2d(&self, transform: &mut Transform2D){
+        let t = self.sample();
+        match self.target{
+            TweenTarget::Loc2D{from, to} => transform.loc = from.lerp(to, t),
+            TweenTarget::Rot2D{from, to} => transform.rot = from + (to - from) * t,
+            TweenTarget::Scale2D{from, to} => transform.scale = from.lerp(to, t),
+            _ => (),
+        }
+    }
+    /// Write the current value to a `Transform3D`
+    pub fn apply_3d(&self, transform: &mut Transform3D){
+        let t = self.sample();
+        match self.target{
+            TweenTarget::Loc3D{from, to} => transform.loc = from.lerp(to, t),
+            TweenTarget::Rot3D{from, to} => transform.rot = Quat::from_euler(from).slerp(Quat::from_euler(to), t).to_euler(),
+            TweenTarget::Scale3D{from, to} => transform.scale = from.lerp(to, t),
+            _ => (),
+        }
+    }
+    /// Write the current color to every pixel of a `CMDSprite`
+    pub fn apply_sprite(&self, sprite: &mut CMDSprite){
+        let t = self.sample();
+        match self.target{
+            TweenTarget::Foreground{from, to} => {
+                let color = lerp_color(from, to, t);
+                sprite.data.iter_mut().for_each(|pixel| pixel.1 = color);
+            }
+            TweenTarget::Background{from, to} => {
+                let color = lerp_color(from, to, t);
+                sprite.data.iter_mut().for_each(|pixel| pixel.2 = color);
+            }
+            _ => (),
+        }
+    }
+}
+impl Component for Tween{
+    type STORAGE = TrackedStorage<Self>;
+
+    const ID: &'static str = "Tween";
+}
+impl TrackedComponent for Tween{
+    const ADDED_ID: &'static str = "TweenAdded";
+    const REMOVED_ID: &'static str = "TweenRemoved";
+}
+
+fn lerp_color(from: (u8, u8, u8), to: (u8, u8, u8), t: f32) -> (u8, u8, u8){
+    // Elastic and Back easing overshoot, so channels have to be clamped
+    let channel = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * t).round().clamp(0.0, 255.0) as u8;
+    (channel(from.0, to.0), channel(from.1, to.1), channel(from.2, to.2))
+}
@@ -1,4 +1,8 @@
+use std::marker::PhantomData;
+
 use super::*;
+use storage::ChangeLog;
+use types::{CommandError, ComponentSnapshot};
 
 /// Announces what Entity has just spawned
 /// 
@@ -9,7 +13,100 @@ impl Event for EntitySpawned{
 }
 
 /// Announced what Entity has just despawned
-pub struct EntityDespawned(pub usize);
+/// 
+/// Provides the now-invalid Token and copies of the Entity's Components,
+/// see `DespawnSnapshots` Resource to choose which Components get captured
+pub struct EntityDespawned(pub Token, pub ComponentSnapshot);
 impl Event for EntityDespawned{
     const ID: &'static str = "EntityDespawned";
-}
\ No newline at end of file
+}
+
+/// Announces that a Command has failed
+/// 
+/// Provides the Command's type name and what went wrong
+pub struct CommandFailed(pub &'static str, pub CommandError);
+impl Event for CommandFailed{
+    const ID: &'static str = "CommandFailed";
+}
+
+/// Announces that a Scene has been loaded
+/// 
+/// Sent after `EntitySpawned` Events of all of it's Entities, provides the Scene's ID
+pub struct SceneLoaded(pub String);
+impl Event for SceneLoaded{
+    const ID: &'static str = "SceneLoaded";
+}
+
+/// Announces that a Scene has been unloaded
+/// 
+/// Sent after `EntityDespawned` Events of all of it's Entities, provides the Scene's ID
+pub struct SceneUnloaded(pub String);
+impl Event for SceneUnloaded{
+    const ID: &'static str = "SceneUnloaded";
+}
+
+/// Announces that an Entity's `Tween` has finished
+/// 
+/// Only sent for `TweenMode::Once`, the `Tween` gets removed from the Entity right after
+pub struct TweenCompleted(pub Token);
+impl Event for TweenCompleted{
+    const ID: &'static str = "TweenCompleted";
+}
+
+/// Announces that an Entity's looping `Tween` has finished a cycle
+/// 
+/// Sent for `TweenMode::Loop` and `TweenMode::PingPong`, each time they reach either end
+pub struct TweenLooped(pub Token);
+impl Event for TweenLooped{
+    const ID: &'static str = "TweenLooped";
+}
+
+/// Announces that a Component has been added to an Entity
+/// 
+/// Sent by `InsertComponent` Command and the other Commands that add Components, provides the Entity's Token.
+/// Spawning Commands send it for each of the new Entity's Components listed in `ComponentTracker` Resource, right after `EntitySpawned`.
+/// Components put straight into their Storage get announced by `ChangeAnnouncer` System at the end of the frame
+pub struct ComponentAdded<C: TrackedComponent>(pub Token, PhantomData<C>);
+impl<C: TrackedComponent> ComponentAdded<C>{
+    pub fn new(token: Token) -> Self{
+        Self(token, PhantomData)
+    }
+}
+impl<C: TrackedComponent> Event for ComponentAdded<C>{
+    const ID: &'static str = C::ADDED_ID;
+}
+
+/// Announces that a Component has been removed from an Entity
+/// 
+/// Sent by `RemoveComponent` Command and the other Commands that remove Components, provides the Entity's Token.
+/// Despawning sends it for each of the Entity's Components listed in `ComponentTracker` Resource, right before `EntityDespawned`.
+/// Components taken straight out of their Storage get announced by `ChangeAnnouncer` System at the end of the frame,
+/// Entities despawned outside of Commands have no Token left to send it with
+pub struct ComponentRemoved<C: TrackedComponent>(pub Token, PhantomData<C>);
+impl<C: TrackedComponent> ComponentRemoved<C>{
+    pub fn new(token: Token) -> Self{
+        Self(token, PhantomData)
+    }
+}
+impl<C: TrackedComponent> Event for ComponentRemoved<C>{
+    const ID: &'static str = C::REMOVED_ID;
+}
+
+/// # Tracked Component trait
+/// Opt-in trait for Components that announce being added to and removed from Entities
+/// 
+/// Provides the IDs for it's `ComponentAdded` and `ComponentRemoved` Events.
+/// The Component has to live in a Storage that logs it's changes, like `TrackedStorage`,
+/// so changes made straight through the Storage get announced too
+/// 
+/// Note: You have to register both Events yourself alongside the Component,
+/// and the Component in `ComponentTracker` Resource for it to be announced on spawn, despawn and every frame
+pub trait TrackedComponent: Component<STORAGE: ChangeLog>{
+    const ADDED_ID: &'static str;
+    const REMOVED_ID: &'static str;
+
+    /// Runs for every Entity whose Component was added, removed or replaced, right before the Events are sent
+    /// 
+    /// Also runs for Entities that have despawned since, which no Events can be sent for
+    fn changed(_world: &World, _id: usize){}
+}
@@ -16,18 +16,69 @@ pub mod systems;
 pub fn init(world: &mut World, disp_build: &mut DispatcherBuilder){
     // -- Components --
     world.register_comp::<comp::CMDSprite>();
+    world.register_comp::<comp::Children>();
+    world.register_comp::<comp::GlobalTransform2D>();
+    world.register_comp::<comp::GlobalTransform3D>();
+    world.register_comp::<comp::Parent>();
+    world.register_comp::<comp::Persistent>();
     world.register_comp::<comp::PlayerController>();
+    world.register_comp::<comp::SceneMember>();
     world.register_comp::<comp::Tags>();
     world.register_comp::<comp::Transform2D>();
     world.register_comp::<comp::Transform3D>();
+    world.register_comp::<comp::Tween>();
 
     // -- Events --
     world.register_event::<events::EntitySpawned>();
     world.register_event::<events::EntityDespawned>();
+    world.register_event::<events::CommandFailed>();
+    world.register_event::<events::ComponentAdded<comp::CMDSprite>>();
+    world.register_event::<events::ComponentRemoved<comp::CMDSprite>>();
+    world.register_event::<events::ComponentAdded<comp::Children>>();
+    world.register_event::<events::ComponentRemoved<comp::Children>>();
+    world.register_event::<events::ComponentAdded<comp::Parent>>();
+    world.register_event::<events::ComponentRemoved<comp::Parent>>();
+    world.register_event::<events::ComponentAdded<comp::Persistent>>();
+    world.register_event::<events::ComponentRemoved<comp::Persistent>>();
+    world.register_event::<events::ComponentAdded<comp::PlayerController>>();
+    world.register_event::<events::ComponentRemoved<comp::PlayerController>>();
+    world.register_event::<events::ComponentAdded<comp::SceneMember>>();
+    world.register_event::<events::ComponentRemoved<comp::SceneMember>>();
+    world.register_event::<events::ComponentAdded<comp::Tags>>();
+    world.register_event::<events::ComponentRemoved<comp::Tags>>();
+    world.register_event::<events::ComponentAdded<comp::Transform2D>>();
+    world.register_event::<events::ComponentRemoved<comp::Transform2D>>();
+    world.register_event::<events::ComponentAdded<comp::Transform3D>>();
+    world.register_event::<events::ComponentRemoved<comp::Transform3D>>();
+    world.register_event::<events::ComponentAdded<comp::Tween>>();
+    world.register_event::<events::ComponentRemoved<comp::Tween>>();
+    world.register_event::<events::TweenCompleted>();
+    world.register_event::<events::TweenLooped>();
+    world.register_event::<events::SceneLoaded>();
+    world.register_event::<events::SceneUnloaded>();
 
     // -- Resources --
     world.register_res::<resources::CMDInput>();
+    world.register_res::<resources::CloneRegistry>();
+    world.register_res::<resources::CommandHistory>();
+    world.register_res::<resources::CommandScheduler>();
+    world.register_res::<resources::ComponentTracker>();
+    world.register_res::<resources::DespawnSnapshots>();
+    world.register_res::<resources::FrameTime>();
+    world.register_res::<resources::PrefabLoader>();
+    world.register_res::<resources::PrefabRegistry>();
+    world.register_res::<resources::SceneRegistry>();
+    world.register_res::<resources::TagIndex>();
+    #[cfg(feature = "serde")]
+    world.register_res::<resources::SnapshotRegistry>();
 
     // -- Systems --
+    disp_build.add::<systems::FrameTimer>();
     disp_build.add::<systems::CMDInputHandler>();
+    disp_build.add::<systems::TagIndexer>();
+    disp_build.add::<systems::ScheduledCommandRunner>();
+    disp_build.add::<systems::TweenRunner>();
+    disp_build.add::<systems::Transform2DPropagator>();
+    disp_build.add::<systems::Transform3DPropagator>();
+    disp_build.add::<systems::ChangeAnnouncer>();
 }
\ No newline at end of file
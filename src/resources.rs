@@ -1,4 +1,23 @@
+use std::any::Any;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+#[cfg(feature = "serde")]
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
 use super::*;
+use manufacture_engine::ECS::entity::EntityBuilder;
+#[cfg(feature = "serde")]
+use manufacture_engine::ECS::request::SystemData;
+use commands::ReversibleCommand;
+use events::TrackedComponent;
+use comp::{Tag, TagId, Tags};
+use types::{ComponentSnapshot, SpawnHandle, EntityPrefab, DEFAULT_PREFAB_ID, PrefabError, PrefabComponent, PrefabDef, PrefabFields, Scene, parse_prefabs, resolve_prefabs};
+#[cfg(feature = "serde")]
+use types::{EntityRecord, Migration, SnapshotError, WorldSnapshot, SNAPSHOT_FORMAT};
+#[cfg(feature = "serde")]
+use serde::{Serialize, de::DeserializeOwned};
+#[cfg(feature = "serde")]
+use serde_json::Value;
 
 pub use crossterm::event::{KeyEvent, KeyCode, KeyModifiers};
 /// # User input -- CMD
@@ -32,4 +51,1229 @@ impl Resource for CMDInput{
             key: KeyEvent::new(KeyCode::Null, KeyModifiers::NONE),
         }
     }
-}
\ No newline at end of file
+}
+
+/// # Frame Time
+/// Time that passed since the previous frame
+/// 
+/// `FrameTimer` System measures it at the start of every frame,
+/// so every System that needs it sees the same value,
+/// use it instead of measuring time on your own:
+/// ```ignore
+/// transform.loc += velocity * time.delta().as_secs_f32();
+/// ```
+/// The first frame is always zero long
+pub struct FrameTime{
+    delta: std::time::Duration,
+    last: Option<std::time::Instant>
+}
+impl FrameTime{
+    /// Get the length of the previous frame
+    pub fn delta(&self) -> std::time::Duration{
+        self.delta
+    }
+    /// Override the length of the current frame, e.g. to step the game by a fixed amount
+    /// 
+    /// `FrameTimer` replaces it with the measured time on the next frame
+    pub fn set_delta(&mut self, delta: std::time::Duration){
+        self.delta = delta
+    }
+    /// Measure the time since the last call
+    pub(crate) fn tick(&mut self){
+        let now = std::time::Instant::now();
+        self.delta = self.last.map(|last| now - last).unwrap_or_default();
+        self.last = Some(now);
+    }
+}
+impl Resource for FrameTime{
+    const ID: &'static str = "FrameTime";
+
+    fn new() -> Self {
+        Self{
+            delta: std::time::Duration::ZERO,
+            last: None,
+        }
+    }
+}
+
+/// Copies a single Component of an Entity, if it has one
+type SnapshotTaker = fn(&World, usize) -> Option<Box<dyn Any>>;
+
+/// The `SnapshotTaker` for a Component type
+fn take_snapshot<C: Component + Clone>(world: &World, id: usize) -> Option<Box<dyn Any>>{
+    world.fetch::<C>().get(&id).map(|comp| Box::new(comp.clone()) as Box<dyn Any>)
+}
+
+/// # Despawn Snapshots
+/// Lists Components that get copied into `EntityDespawned` Event before the Entity is removed
+/// 
+/// Nothing is captured by default, register the Components you need yourself:
+/// ```ignore
+/// world.fetch_res_mut::<DespawnSnapshots>().register::<Transform2D>();
+/// ```
+pub struct DespawnSnapshots{
+    takers: Vec<(&'static str, SnapshotTaker)>
+}
+impl DespawnSnapshots{
+    /// Capture the given Component when an Entity despawns
+    pub fn register<C: Component + Clone>(&mut self){
+        if self.takers.iter().any(|(id, _)| *id == C::ID){
+            return
+        }
+        self.takers.push((C::ID, take_snapshot::<C>));
+    }
+    /// Stop capturing the given Component
+    pub fn unregister<C: Component>(&mut self){
+        self.takers.retain(|(id, _)| *id != C::ID);
+    }
+    /// Copy the registered Components of an Entity
+    pub fn capture(&self, world: &World, id: usize) -> ComponentSnapshot{
+        let mut snapshot = ComponentSnapshot::new();
+        for (comp_id, taker) in self.takers.iter(){
+            if let Some(comp) = taker(world, id){
+                snapshot.insert_boxed(comp_id, comp);
+            }
+        }
+        snapshot
+    }
+}
+impl Resource for DespawnSnapshots{
+    const ID: &'static str = "DespawnSnapshots";
+
+    fn new() -> Self {
+        Self{
+            takers: Vec::new(),
+        }
+    }
+}
+
+/// Copy, capture and restore instructions for a single Component type
+struct ComponentCloner{
+    id: &'static str,
+    /// Copies the Component from one Entity to another, if the first one has it
+    copy: fn(&World, usize, usize),
+    /// Copies the Component out of an Entity, if it has it
+    capture: SnapshotTaker,
+    /// Puts the Component from a Snapshot back on an Entity, if it's there
+    restore: fn(&World, usize, &mut ComponentSnapshot)
+}
+
+/// # Clone Registry
+/// Lists Components that get copied by `CloneEntity` Command
+/// 
+/// `Transform2D`, `Transform3D`, `Tags` and `CMDSprite` are registered out of the box.
+/// `PlayerController` is left out so that clones don't get controlled by the player,
+/// and `Parent`/`Children` are never copied, as the hierarchy links would go out of sync
+/// 
+/// Register your own Components with:
+/// ```ignore
+/// world.fetch_res_mut::<CloneRegistry>().register::<Health>();
+/// ```
+pub struct CloneRegistry{
+    cloners: Vec<ComponentCloner>
+}
+impl CloneRegistry{
+    /// Copy the given Component when cloning Entities
+    pub fn register<C: Component + Clone>(&mut self){
+        if self.is_registered::<C>(){
+            return
+        }
+        self.cloners.push(ComponentCloner{
+            id: C::ID,
+            copy: |world, from, to| {
+                let comp = match world.fetch::<C>().get(&from){
+                    Some(comp) => comp.clone(),
+                    None => return,
+                };
+                world.fetch_mut::<C>().insert(to, comp);
+            },
+            capture: take_snapshot::<C>,
+            restore: |world, id, snapshot| {
+                if let Some(comp) = snapshot.take::<C>(){
+                    world.fetch_mut::<C>().insert(id, comp);
+                }
+            },
+        });
+    }
+    /// Stop copying the given Component
+    pub fn unregister<C: Component>(&mut self){
+        self.cloners.retain(|cloner| cloner.id != C::ID);
+    }
+    /// Check if the given Component gets copied
+    pub fn is_registered<C: Component>(&self) -> bool{
+        self.cloners.iter().any(|cloner| cloner.id == C::ID)
+    }
+    /// Copy all registered Components from one Entity to another
+    pub fn clone_components(&self, world: &World, from: usize, to: usize){
+        for cloner in self.cloners.iter(){
+            (cloner.copy)(world, from, to);
+        }
+    }
+    /// Copy all registered Components of an Entity into a Snapshot
+    pub fn capture(&self, world: &World, id: usize) -> ComponentSnapshot{
+        let mut snapshot = ComponentSnapshot::new();
+        for cloner in self.cloners.iter(){
+            if let Some(comp) = (cloner.capture)(world, id){
+                snapshot.insert_boxed(cloner.id, comp);
+            }
+        }
+        snapshot
+    }
+    /// Put all registered Components from a Snapshot on an Entity
+    pub fn restore(&self, world: &World, id: usize, snapshot: &mut ComponentSnapshot){
+        for cloner in self.cloners.iter(){
+            (cloner.restore)(world, id, snapshot);
+        }
+    }
+}
+impl Resource for CloneRegistry{
+    const ID: &'static str = "CloneRegistry";
+
+    fn new() -> Self {
+        let mut registry = Self{
+            cloners: Vec::new(),
+        };
+        registry.register::<comp::CMDSprite>();
+        registry.register::<comp::Tags>();
+        registry.register::<comp::Transform2D>();
+        registry.register::<comp::Transform3D>();
+        registry
+    }
+}
+
+/// Takes a single Component type off an Entity
+type ComponentStripper = fn(&World, usize);
+/// Sends the Events for a single Component type's Storage changes, see `commands::announce`
+type ComponentAnnouncer = fn(&World);
+
+/// # Component Tracker
+/// Lists `TrackedComponent`s that announce being added to and removed from Entities
+/// 
+/// Changes go through their Storage, so it doesn't matter if a Command or a System made them:
+/// Core Commands announce them right away, and so does spawning and despawning,
+/// the rest are announced by `ChangeAnnouncer` System once per frame.
+/// 
+/// Core Components are registered out of the box, register your own alongside their Events:
+/// ```ignore
+/// world.register_event::<ComponentAdded<Health>>();
+/// world.register_event::<ComponentRemoved<Health>>();
+/// world.fetch_res_mut::<ComponentTracker>().register::<Health>();
+/// ```
+pub struct ComponentTracker{
+    tracked: Vec<(&'static str, ComponentStripper, ComponentAnnouncer)>
+}
+impl ComponentTracker{
+    /// Announce the given Component's changes
+    pub fn register<C: TrackedComponent>(&mut self){
+        if self.is_registered::<C>(){
+            return
+        }
+        self.tracked.push((
+            C::ID,
+            |world, id| world.fetch_mut::<C>().remove(&id),
+            commands::announce::<C>,
+        ));
+    }
+    /// Stop announcing the given Component, only Commands changing it directly still do
+    pub fn unregister<C: TrackedComponent>(&mut self){
+        self.tracked.retain(|(id, _, _)| *id != C::ID);
+    }
+    /// Check if the given Component gets announced
+    pub fn is_registered<C: TrackedComponent>(&self) -> bool{
+        self.tracked.iter().any(|(id, _, _)| *id == C::ID)
+    }
+    /// Send the Events for every registered Component's changes that weren't announced yet
+    pub fn announce(&self, world: &World){
+        for (_, _, announce) in self.tracked.iter(){
+            announce(world);
+        }
+    }
+    /// Take every registered Component off the Entity, without announcing it yet
+    pub(crate) fn strip(&self, world: &World, id: usize){
+        for (_, strip, _) in self.tracked.iter(){
+            strip(world, id);
+        }
+    }
+}
+impl Resource for ComponentTracker{
+    const ID: &'static str = "ComponentTracker";
+
+    fn new() -> Self {
+        let mut tracker = Self{
+            tracked: Vec::new(),
+        };
+        tracker.register::<comp::CMDSprite>();
+        tracker.register::<comp::Children>();
+        tracker.register::<comp::Parent>();
+        tracker.register::<comp::Persistent>();
+        tracker.register::<comp::PlayerController>();
+        tracker.register::<comp::SceneMember>();
+        tracker.register::<comp::Tags>();
+        tracker.register::<comp::Transform2D>();
+        tracker.register::<comp::Transform3D>();
+        tracker.register::<comp::Tween>();
+        tracker
+    }
+}
+
+/// A Prefab's spawning instructions with it's data baked in
+pub type PrefabSpawner = Arc<dyn Fn(EntityBuilder<'_>) + Send + Sync>;
+
+/// # Prefab Registry
+/// Stores Prefabs under their IDs so they can be spawned by name
+/// 
+/// See `SpawnPrefabById` Command
+pub struct PrefabRegistry{
+    inner: HashMap<String, PrefabSpawner>
+}
+impl PrefabRegistry{
+    /// Register a Prefab under it's `ID`
+    /// 
+    /// Fails if the Prefab didn't override the default `ID`
+    pub fn register<T: EntityPrefab + Send + Sync + 'static>(&mut self, prefab: T) -> Result<(), PrefabError>{
+        if T::ID == DEFAULT_PREFAB_ID{
+            return Err(PrefabError::DefaultID(std::any::type_name::<T>().to_string()))
+        }
+        self.register_spawner(T::ID, Arc::new(move |builder| prefab.spawn(builder)))
+    }
+    /// Register raw spawning instructions under a given ID
+    /// 
+    /// Useful for Prefabs that only exist at runtime
+    pub fn register_spawner(&mut self, id: &str, spawner: PrefabSpawner) -> Result<(), PrefabError>{
+        if self.inner.contains_key(id){
+            return Err(PrefabError::DuplicateID(id.to_string()))
+        }
+        self.inner.insert(id.to_string(), spawner);
+        Ok(())
+    }
+    /// Remove a Prefab from the Registry
+    pub fn unregister(&mut self, id: &str){
+        self.inner.remove(id);
+    }
+    /// Check if a Prefab with a given ID is registered
+    pub fn contains(&self, id: &str) -> bool{
+        self.inner.contains_key(id)
+    }
+    /// Get the spawning instructions of a Prefab
+    pub fn get(&self, id: &str) -> Option<PrefabSpawner>{
+        self.inner.get(id).cloned()
+    }
+    /// Iterate over IDs of all registered Prefabs
+    pub fn ids(&self) -> impl Iterator<Item = &str>{
+        self.inner.keys().map(|id| id.as_str())
+    }
+}
+impl Resource for PrefabRegistry{
+    const ID: &'static str = "PrefabRegistry";
+
+    fn new() -> Self {
+        Self{
+            inner: HashMap::new(),
+        }
+    }
+}
+
+/// # Scene Registry
+/// Stores Scenes under their IDs and keeps track of which ones are loaded
+/// 
+/// See `LoadScene`, `UnloadScene` and `SwitchScene` Commands
+pub struct SceneRegistry{
+    scenes: HashMap<String, Scene>,
+    loaded: Vec<String>
+}
+impl SceneRegistry{
+    /// Register a Scene under it's ID
+    /// 
+    /// Returns the Scene it replaced, if there was one.
+    /// Already loaded Entities of the old Scene are left alone
+    pub fn register(&mut self, scene: Scene) -> Option<Scene>{
+        self.scenes.insert(scene.id().to_string(), scene)
+    }
+    /// Remove a Scene from the Registry
+    /// 
+    /// It's Entities stay loaded, send `UnloadScene` for those
+    pub fn unregister(&mut self, id: &str) -> Option<Scene>{
+        self.scenes.remove(id)
+    }
+    /// Check if a Scene with a given ID is registered
+    pub fn contains(&self, id: &str) -> bool{
+        self.scenes.contains_key(id)
+    }
+    pub fn get(&self, id: &str) -> Option<&Scene>{
+        self.scenes.get(id)
+    }
+    /// Iterate over IDs of all registered Scenes
+    pub fn ids(&self) -> impl Iterator<Item = &str>{
+        self.scenes.keys().map(|id| id.as_str())
+    }
+    /// Check if a Scene is currently loaded
+    pub fn is_loaded(&self, id: &str) -> bool{
+        self.loaded.iter().any(|loaded| loaded == id)
+    }
+    /// Iterate over IDs of loaded Scenes, in the order they were loaded
+    pub fn loaded(&self) -> impl Iterator<Item = &str>{
+        self.loaded.iter().map(|id| id.as_str())
+    }
+    pub(crate) fn set_loaded(&mut self, id: &str){
+        if !self.is_loaded(id){
+            self.loaded.push(id.to_string());
+        }
+    }
+    pub(crate) fn set_unloaded(&mut self, id: &str){
+        self.loaded.retain(|loaded| loaded != id);
+    }
+}
+impl Resource for SceneRegistry{
+    const ID: &'static str = "SceneRegistry";
+
+    fn new() -> Self {
+        Self{
+            scenes: HashMap::new(),
+            loaded: Vec::new(),
+        }
+    }
+}
+
+/// Adds a single Component to an Entity being built
+pub type ComponentFactory = Arc<dyn Fn(EntityBuilder<'_>) -> EntityBuilder<'_> + Send + Sync>;
+
+/// Turns a Prefab file section into a `ComponentFactory`
+type ComponentDeserializer = fn(&PrefabFields) -> Result<ComponentFactory, PrefabError>;
+
+/// # Prefab Loader
+/// Turns Prefab files into Prefabs in `PrefabRegistry`
+/// 
+/// See `parse_prefabs` in `types` for the file format
+/// 
+/// Core Components are registered out of the box,
+/// plugins register their own Components by implementing `PrefabComponent` for them:
+/// ```ignore
+/// world.fetch_res_mut::<PrefabLoader>().register::<Health>();
+/// ```
+pub struct PrefabLoader{
+    deserializers: HashMap<&'static str, ComponentDeserializer>,
+    /// `PrefabComponent::list_separator` of every registered Component
+    separators: HashMap<&'static str, fn(&str) -> &'static str>,
+    /// Resolved definitions of everything loaded so far, so that later files can extend them
+    loaded: HashMap<String, PrefabDef>
+}
+impl PrefabLoader{
+    /// Allow the Component to be used in Prefab files under it's `ID`
+    pub fn register<C: PrefabComponent>(&mut self){
+        self.deserializers.insert(C::ID, |fields| {
+            let comp = C::from_fields(fields)?;
+            Ok(Arc::new(move |builder| builder.with(comp.clone())))
+        });
+        self.separators.insert(C::ID, C::list_separator);
+    }
+    /// Check if a Component can be used in Prefab files
+    pub fn is_registered(&self, comp: &str) -> bool{
+        self.deserializers.contains_key(comp)
+    }
+    /// Get the resolved definition of a Prefab loaded from a file
+    pub fn get_def(&self, id: &str) -> Option<&PrefabDef>{
+        self.loaded.get(id)
+    }
+    /// Load all Prefabs from a file into the Registry
+    /// 
+    /// Prefabs may extend Prefabs from the same file or from any file loaded before.
+    /// 
+    /// Returns IDs of the loaded Prefabs.
+    /// If anything in the file is wrong, nothing gets registered
+    pub fn load_file(&mut self, path: impl AsRef<std::path::Path>, registry: &mut PrefabRegistry) -> Result<Vec<String>, PrefabError>{
+        let file = path.as_ref().display().to_string();
+        let source = std::fs::read_to_string(path.as_ref()).map_err(|err| PrefabError::Io{
+            file: file.clone(),
+            message: err.to_string(),
+        })?;
+        self.load_str(&file, &source, registry)
+    }
+    /// Load all Prefabs from text into the Registry
+    /// 
+    /// `file` is only used for error messages.
+    /// If anything in the text is wrong, nothing gets registered
+    pub fn load_str(&mut self, file: &str, source: &str, registry: &mut PrefabRegistry) -> Result<Vec<String>, PrefabError>{
+        let defs = resolve_prefabs(&parse_prefabs(file, source)?, &self.loaded, &|comp, field| self.separator(comp, field))?;
+
+        let mut spawners = Vec::new();
+        for def in defs.iter(){
+            if registry.contains(&def.id){
+                return Err(PrefabError::DuplicateID(def.id.clone()))
+            }
+            spawners.push((def.id.clone(), self.build(def)?));
+        }
+
+        let mut ids = Vec::new();
+        for ((id, spawner), def) in spawners.into_iter().zip(defs){
+            registry.register_spawner(&id, spawner)?;
+            self.loaded.insert(id.clone(), def);
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+    /// Load a Scene from a file into the Registry, see `load_scene_str`
+    pub fn load_scene_file(&self, id: &str, path: impl AsRef<std::path::Path>, scenes: &mut SceneRegistry) -> Result<(), PrefabError>{
+        let file = path.as_ref().display().to_string();
+        let source = std::fs::read_to_string(path.as_ref()).map_err(|err| PrefabError::Io{
+            file: file.clone(),
+            message: err.to_string(),
+        })?;
+        self.load_scene_str(id, &file, &source, scenes)
+    }
+    /// Load a Scene from text into the Registry, replacing the Scene with the same ID if there was one
+    /// 
+    /// Scenes use the Prefab file format, every Prefab in the text becomes a single Entity of the Scene.
+    /// They may extend Prefabs loaded from files before, but aren't registered as Prefabs themselves:
+    /// ```text
+    /// [player]
+    /// extends = hero
+    /// 
+    /// [player.Persistent]
+    /// 
+    /// [orc_1]
+    /// extends = orc
+    /// 
+    /// [orc_1.Transform2D]
+    /// loc = 8.0, 2.0
+    /// ```
+    /// `file` is only used for error messages.
+    /// If anything in the text is wrong, the Registry is left as it was
+    pub fn load_scene_str(&self, id: &str, file: &str, source: &str, scenes: &mut SceneRegistry) -> Result<(), PrefabError>{
+        let mut scene = Scene::new(id);
+        for def in resolve_prefabs(&parse_prefabs(file, source)?, &self.loaded, &|comp, field| self.separator(comp, field))?{
+            scene.add_spawner(self.build(&def)?);
+        }
+        scenes.register(scene);
+        Ok(())
+    }
+    /// What `+=` appends to a list field of a Component with, see `PrefabComponent::list_separator`
+    pub fn separator(&self, comp: &str, field: &str) -> &'static str{
+        self.separators.get(comp).map_or(", ", |separator| separator(field))
+    }
+    /// Turn a resolved Prefab definition into spawning instructions
+    pub fn build(&self, def: &PrefabDef) -> Result<PrefabSpawner, PrefabError>{
+        let mut factories = Vec::new();
+        for fields in def.components.iter(){
+            let deserializer = self.deserializers.get(fields.comp())
+                .ok_or_else(|| fields.error(format!("Unknown Component `{}`", fields.comp())))?;
+            factories.push(deserializer(fields)?);
+        }
+
+        Ok(Arc::new(move |builder| {
+            let mut builder = builder;
+            for factory in factories.iter(){
+                builder = factory(builder);
+            }
+        }))
+    }
+}
+impl Resource for PrefabLoader{
+    const ID: &'static str = "PrefabLoader";
+
+    fn new() -> Self {
+        let mut loader = Self{
+            deserializers: HashMap::new(),
+            separators: HashMap::new(),
+            loaded: HashMap::new(),
+        };
+        loader.register::<comp::CMDSprite>();
+        loader.register::<comp::Persistent>();
+        loader.register::<comp::PlayerController>();
+        loader.register::<comp::Tags>();
+        loader.register::<comp::Transform2D>();
+        loader.register::<comp::Transform3D>();
+        loader
+    }
+}
+
+/// A group of Commands that get undone and redone together
+pub struct Transaction{
+    pub label: String,
+    /// Reverse Commands in the order their originals ran
+    commands: Vec<Box<dyn ReversibleCommand>>
+}
+impl Transaction{
+    pub fn new(label: impl Into<String>) -> Self{
+        Self{
+            label: label.into(),
+            commands: Vec::new(),
+        }
+    }
+    pub fn len(&self) -> usize{
+        self.commands.len()
+    }
+    pub fn is_empty(&self) -> bool{
+        self.commands.is_empty()
+    }
+    /// Run the reverse Commands, last one first
+    /// 
+    /// Returns a Transaction that reverses this one
+    pub(crate) fn run(self, world: &mut World) -> Transaction{
+        let mut reverse = Transaction::new(self.label);
+        for mut command in self.commands.into_iter().rev(){
+            if let Some(inverse) = command.apply(world){
+                reverse.commands.push(inverse);
+            }
+        }
+        reverse
+    }
+}
+
+/// # Command History
+/// Holds undo and redo stacks of `ReversibleCommand`s
+/// 
+/// Commands end up here when sent wrapped in `Record`, use `Undo` and `Redo` Commands to move through the history.
+/// Group several Commands into one step with `BeginTransaction` and `EndTransaction`
+/// 
+/// Recording a new Command clears the redo stack.
+/// Once there's more than `max_depth` steps, the oldest ones are forgotten
+/// 
+/// Undoing a despawn brings back the Entity's place in the hierarchy and the Components registered here.
+/// `CMDSprite`, `Persistent`, `PlayerController`, `SceneMember`, `Tags`, `Transform2D`, `Transform3D` and `Tween`
+/// are registered out of the box, add your own with:
+/// ```ignore
+/// world.fetch_res_mut::<CommandHistory>().register::<Health>();
+/// ```
+pub struct CommandHistory{
+    undo: VecDeque<Transaction>,
+    redo: Vec<Transaction>,
+    open: Option<Transaction>,
+    max_depth: usize,
+    /// Entities touched by the history, by their current ID
+    handles: HashMap<usize, SpawnHandle>,
+    /// Components brought back when undoing a despawn
+    restored: CloneRegistry
+}
+impl CommandHistory{
+    /// Restore the given Component when undoing a despawn
+    pub fn register<C: Component + Clone>(&mut self){
+        self.restored.register::<C>();
+    }
+    /// Stop restoring the given Component
+    pub fn unregister<C: Component>(&mut self){
+        self.restored.unregister::<C>();
+    }
+    /// Check if the given Component gets restored
+    pub fn is_registered<C: Component>(&self) -> bool{
+        self.restored.is_registered::<C>()
+    }
+    /// Store the reverse of a Command that has just run
+    pub fn record(&mut self, inverse: Box<dyn ReversibleCommand>){
+        match &mut self.open{
+            Some(transaction) => transaction.commands.push(inverse),
+            None => {
+                let mut transaction = Transaction::new("");
+                transaction.commands.push(inverse);
+                self.redo.clear();
+                self.push_undo(transaction);
+            }
+        }
+    }
+    /// Start grouping recorded Commands into a single step
+    /// 
+    /// Closes the currently open Transaction if there is one
+    pub fn begin(&mut self, label: impl Into<String>){
+        self.end();
+        self.open = Some(Transaction::new(label));
+    }
+    /// Stop grouping recorded Commands
+    pub fn end(&mut self){
+        if let Some(transaction) = self.open.take() && !transaction.is_empty(){
+            self.redo.clear();
+            self.push_undo(transaction);
+        }
+    }
+    pub fn can_undo(&self) -> bool{
+        !self.undo.is_empty()
+    }
+    pub fn can_redo(&self) -> bool{
+        !self.redo.is_empty()
+    }
+    /// Label of the step that would be undone next
+    pub fn undo_label(&self) -> Option<&str>{
+        self.undo.back().map(|transaction| transaction.label.as_str())
+    }
+    /// Label of the step that would be redone next
+    pub fn redo_label(&self) -> Option<&str>{
+        self.redo.last().map(|transaction| transaction.label.as_str())
+    }
+    pub fn max_depth(&self) -> usize{
+        self.max_depth
+    }
+    /// Set how many steps are remembered, forgetting the oldest ones if there's too many
+    pub fn set_max_depth(&mut self, max_depth: usize){
+        self.max_depth = max_depth;
+        while self.undo.len() > self.max_depth{
+            self.undo.pop_front();
+        }
+        self.redo.truncate(self.max_depth);
+        self.prune();
+    }
+    /// Forget the whole history
+    pub fn clear(&mut self){
+        self.undo.clear();
+        self.redo.clear();
+        self.open = None;
+        self.handles.clear();
+    }
+    /// Get the handle the history uses to refer to an Entity
+    /// 
+    /// The handle follows the Entity when undoing respawns it with a new Token
+    pub fn handle(&mut self, token: Token) -> SpawnHandle{
+        match self.handles.get(&token.id()){
+            Some(handle) if handle.get() == Some(token) => handle.clone(),
+            _ => {
+                let handle = SpawnHandle::new();
+                handle.set(token);
+                self.handles.insert(token.id(), handle.clone());
+                handle
+            }
+        }
+    }
+    /// Point a handle to a respawned Entity
+    pub(crate) fn rebind(&mut self, handle: &SpawnHandle, token: Token){
+        handle.set(token);
+        self.handles.insert(token.id(), handle.clone());
+    }
+    pub(crate) fn pop_undo(&mut self) -> Option<Transaction>{
+        self.end();
+        self.undo.pop_back()
+    }
+    pub(crate) fn pop_redo(&mut self) -> Option<Transaction>{
+        self.end();
+        self.redo.pop()
+    }
+    /// Push an undo step without touching the redo stack
+    pub(crate) fn push_undo(&mut self, transaction: Transaction){
+        self.undo.push_back(transaction);
+        while self.undo.len() > self.max_depth{
+            self.undo.pop_front();
+        }
+        self.prune();
+    }
+    pub(crate) fn push_redo(&mut self, transaction: Transaction){
+        self.redo.push(transaction);
+    }
+    /// Copy the registered Components of an Entity that's about to despawn
+    pub(crate) fn capture(&self, world: &World, id: usize) -> ComponentSnapshot{
+        self.restored.capture(world, id)
+    }
+    /// Put the Components captured on despawn back on a respawned Entity
+    pub(crate) fn restore(&self, world: &World, id: usize, snapshot: &mut ComponentSnapshot){
+        self.restored.restore(world, id, snapshot);
+    }
+    /// Forget handles of Entities no step refers to anymore
+    fn prune(&mut self){
+        self.handles.retain(|_, handle| handle.is_shared());
+    }
+}
+impl Resource for CommandHistory{
+    const ID: &'static str = "CommandHistory";
+
+    fn new() -> Self {
+        Self{
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            open: None,
+            max_depth: 100,
+            handles: HashMap::new(),
+            restored: {
+                let mut restored = CloneRegistry{
+                    cloners: Vec::new(),
+                };
+                restored.register::<comp::CMDSprite>();
+                restored.register::<comp::Persistent>();
+                restored.register::<comp::PlayerController>();
+                restored.register::<comp::SceneMember>();
+                restored.register::<comp::Tags>();
+                restored.register::<comp::Transform2D>();
+                restored.register::<comp::Transform3D>();
+                restored.register::<comp::Tween>();
+                restored
+            },
+        }
+    }
+}
+
+/// Identifies a Command waiting in `CommandScheduler`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ScheduleHandle(u64);
+
+/// When a scheduled Command is due
+enum Due{
+    Frame(u64),
+    Time(std::time::Duration)
+}
+
+struct Scheduled{
+    handle: ScheduleHandle,
+    due: Due,
+    command: Box<dyn Command>
+}
+
+/// # Command Scheduler
+/// Holds Commands that should run later, after a number of frames or after some time
+/// 
+/// `ScheduledCommandRunner` System sends them off once they're due, in the order they were scheduled:
+/// ```ignore
+/// scheduler.after(Duration::from_secs_f32(0.5), DespawnToken(explosion));
+/// let wave = scheduler.after_frames(600, SpawnPrefabById::new("wave_2"));
+/// scheduler.cancel(wave);
+/// ```
+pub struct CommandScheduler{
+    frame: u64,
+    elapsed: std::time::Duration,
+    next_handle: u64,
+    pending: Vec<Scheduled>
+}
+impl CommandScheduler{
+    /// Run a Command after `frames` frames have passed
+    pub fn after_frames<C: Command>(&mut self, frames: u64, command: C) -> ScheduleHandle{
+        self.schedule(Due::Frame(self.frame + frames), Box::new(command))
+    }
+    /// Run a Command after `delay` has passed
+    /// 
+    /// The delay is checked once per frame, so the Command runs on the first frame after it
+    pub fn after<C: Command>(&mut self, delay: std::time::Duration, command: C) -> ScheduleHandle{
+        self.schedule(Due::Time(self.elapsed + delay), Box::new(command))
+    }
+    /// Drop a scheduled Command before it runs
+    /// 
+    /// Returns `false` if it has already run or was cancelled before
+    pub fn cancel(&mut self, handle: ScheduleHandle) -> bool{
+        let count = self.pending.len();
+        self.pending.retain(|scheduled| scheduled.handle != handle);
+        self.pending.len() != count
+    }
+    /// Check if a scheduled Command is still waiting
+    pub fn is_pending(&self, handle: ScheduleHandle) -> bool{
+        self.pending.iter().any(|scheduled| scheduled.handle == handle)
+    }
+    /// Drop all scheduled Commands
+    pub fn clear(&mut self){
+        self.pending.clear();
+    }
+    pub fn len(&self) -> usize{
+        self.pending.len()
+    }
+    pub fn is_empty(&self) -> bool{
+        self.pending.is_empty()
+    }
+    /// Number of frames the Scheduler has gone through
+    pub fn frame(&self) -> u64{
+        self.frame
+    }
+    /// Time the Scheduler has gone through
+    pub fn elapsed(&self) -> std::time::Duration{
+        self.elapsed
+    }
+
+    fn schedule(&mut self, due: Due, command: Box<dyn Command>) -> ScheduleHandle{
+        let handle = ScheduleHandle(self.next_handle);
+        self.next_handle += 1;
+        self.pending.push(Scheduled{handle, due, command});
+        handle
+    }
+    /// Move to the next frame and take out all Commands that are due
+    pub(crate) fn advance(&mut self, delta: std::time::Duration) -> Vec<Box<dyn Command>>{
+        self.frame += 1;
+        self.elapsed += delta;
+
+        let mut due = Vec::new();
+        let mut index = 0;
+        while index < self.pending.len(){
+            let ready = match self.pending[index].due{
+                Due::Frame(frame) => frame <= self.frame,
+                Due::Time(time) => time <= self.elapsed,
+            };
+            if ready{
+                due.push(self.pending.remove(index).command);
+            }else{
+                index += 1;
+            }
+        }
+        due
+    }
+}
+impl Resource for CommandScheduler{
+    const ID: &'static str = "CommandScheduler";
+
+    fn new() -> Self {
+        Self{
+            frame: 0,
+            elapsed: std::time::Duration::ZERO,
+            next_handle: 0,
+            pending: Vec::new(),
+        }
+    }
+}
+
+/// # Tag Index
+/// Keeps track of which Entities have which tags, so they can be found without going through every `Tags` Component
+/// 
+/// Core Commands update it as they go: spawning, despawning, `InsertComponent`/`RemoveComponent` of `Tags`,
+/// and `AddTag`/`RemoveTag`, which are the preferred way to tag Entities.
+/// Anything done outside of them can leave it behind for a while:
+/// `Tags` put in or taken out through the Storage are caught up with by `ChangeAnnouncer` System at the end of the frame,
+/// `Tags` changed in place and Entities despawned directly by `TagIndexer` System on the next one.
+/// Call `sync` yourself if you need the index up to date sooner:
+/// ```ignore
+/// tags.tag::<Enemy>();
+/// index.sync(id, Some(tags));
+/// ```
+/// Also used by `WithTag` and `WithoutTag` Query Filters, which don't have to wait for it
+pub struct TagIndex{
+    by_tag: HashMap<TagId, BTreeSet<usize>>,
+    by_entity: HashMap<usize, HashSet<TagId>>
+}
+impl TagIndex{
+    /// Iterate over IDs of Entities with the given tag, in ascending order
+    pub fn entities(&self, tag: TagId) -> impl Iterator<Item = usize> + '_{
+        self.by_tag.get(&tag).into_iter().flatten().copied()
+    }
+    /// Iterate over IDs of Entities with the given `Tag`, in ascending order
+    pub fn entities_with<T: Tag>(&self) -> impl Iterator<Item = usize> + '_{
+        self.entities(TagId::of::<T>())
+    }
+    /// Number of Entities with the given tag
+    pub fn count(&self, tag: TagId) -> usize{
+        self.by_tag.get(&tag).map_or(0, |entities| entities.len())
+    }
+    /// Check if the Entity has the given tag
+    pub fn has(&self, id: usize, tag: TagId) -> bool{
+        self.by_entity.get(&id).is_some_and(|tags| tags.contains(&tag))
+    }
+    /// Iterate over the Entity's tags, in no particular order
+    pub fn tags_of(&self, id: usize) -> impl Iterator<Item = TagId> + '_{
+        self.by_entity.get(&id).into_iter().flatten().copied()
+    }
+    /// Bring the Entity's entries up to date with it's `Tags`
+    /// 
+    /// Pass `None` if the Entity no longer has `Tags` or has despawned
+    pub fn sync(&mut self, id: usize, tags: Option<&Tags>){
+        let old = self.by_entity.remove(&id).unwrap_or_default();
+        for tag in old{
+            self.unlink(id, tag);
+        }
+        for tag in tags.into_iter().flat_map(|tags| tags.iter()){
+            self.insert(id, tag);
+        }
+    }
+    /// Drop every Entity that doesn't pass `keep` from the index
+    pub fn retain(&mut self, keep: impl Fn(usize) -> bool){
+        let dropped: Vec<usize> = self.by_entity.keys().copied().filter(|id| !keep(*id)).collect();
+        for id in dropped{
+            self.sync(id, None);
+        }
+    }
+    /// Record that the Entity has the given tag
+    pub fn insert(&mut self, id: usize, tag: TagId){
+        self.by_tag.entry(tag).or_default().insert(id);
+        self.by_entity.entry(id).or_default().insert(tag);
+    }
+    /// Record that the Entity no longer has the given tag
+    pub fn remove(&mut self, id: usize, tag: TagId){
+        self.unlink(id, tag);
+        if let Some(tags) = self.by_entity.get_mut(&id){
+            tags.remove(&tag);
+            if tags.is_empty(){
+                self.by_entity.remove(&id);
+            }
+        }
+    }
+    /// Removes the Entity from the tag's set, dropping the set once it's empty
+    fn unlink(&mut self, id: usize, tag: TagId){
+        if let Some(entities) = self.by_tag.get_mut(&tag){
+            entities.remove(&id);
+            if entities.is_empty(){
+                self.by_tag.remove(&tag);
+            }
+        }
+    }
+}
+impl Resource for TagIndex{
+    const ID: &'static str = "TagIndex";
+
+    fn new() -> Self {
+        Self{
+            by_tag: HashMap::new(),
+            by_entity: HashMap::new(),
+        }
+    }
+}
+
+/// Puts a decoded Component on an Entity
+#[cfg(feature = "serde")]
+pub(crate) type ComponentInserter = Box<dyn FnOnce(&World, usize)>;
+/// Overwrites a Resource with a decoded one
+#[cfg(feature = "serde")]
+pub(crate) type ResourceInserter = Box<dyn FnOnce(&World)>;
+
+/// Current version of a saved type and Migrations from it's older versions
+#[cfg(feature = "serde")]
+struct Versioning{
+    version: u32,
+    migrations: BTreeMap<u32, Migration>
+}
+#[cfg(feature = "serde")]
+impl Versioning{
+    fn new() -> Self{
+        Self{
+            version: 1,
+            migrations: BTreeMap::new(),
+        }
+    }
+    fn add_migration(&mut self, from: u32, migration: Migration){
+        self.migrations.insert(from, migration);
+        self.version = self.version.max(from + 1);
+    }
+    /// Run the data through every Migration from `found` up to the current version
+    fn migrate(&self, id: &str, found: u32, mut data: Value) -> Result<Value, SnapshotError>{
+        if found > self.version{
+            return Err(SnapshotError::NewerVersion{id: id.to_string(), found, supported: self.version})
+        }
+        for from in found..self.version{
+            let migration = self.migrations.get(&from)
+                .ok_or_else(|| SnapshotError::MissingMigration{id: id.to_string(), from})?;
+            data = migration(data).map_err(|message| SnapshotError::Migration{id: id.to_string(), from, message})?;
+        }
+        Ok(data)
+    }
+}
+
+/// Save and load instructions for a single Component type
+#[cfg(feature = "serde")]
+struct ComponentFormat{
+    id: &'static str,
+    versioning: Versioning,
+    /// IDs of every Entity that has the Component
+    ids: fn(&World) -> Vec<usize>,
+    /// Turns the Component of an Entity into JSON, if it has it
+    encode: fn(&World, usize) -> Option<serde_json::Result<Value>>,
+    decode: fn(Value) -> serde_json::Result<ComponentInserter>
+}
+
+/// Save and load instructions for a single Resource type
+#[cfg(feature = "serde")]
+struct ResourceFormat{
+    id: &'static str,
+    versioning: Versioning,
+    encode: fn(&World) -> serde_json::Result<Value>,
+    decode: fn(Value) -> serde_json::Result<ResourceInserter>
+}
+
+/// A Snapshot turned back into Components and Resources, ready to be put into the World
+#[cfg(feature = "serde")]
+pub(crate) struct DecodedSnapshot{
+    pub(crate) entities: Vec<(usize, Vec<ComponentInserter>)>,
+    pub(crate) resources: Vec<ResourceInserter>
+}
+
+/// # Snapshot Registry
+/// Lists Components and Resources that get saved into a `WorldSnapshot`
+/// 
+/// `Transform2D`, `Transform3D`, `Tags`, `CMDSprite`, `PlayerController`, `SceneMember` and `Persistent` are registered out of the box.
+/// No Resources are registered by default
+/// 
+/// Register your own with:
+/// ```ignore
+/// let mut registry = world.fetch_res_mut::<SnapshotRegistry>();
+/// registry.register_component::<Health>();
+/// registry.register_resource::<Score>();
+/// ```
+/// 
+/// When the layout of a Component changes, add a Migration from the old version.
+/// This also bumps the Component's version, saves made before that get upgraded when loaded:
+/// ```ignore
+/// // Version 1 stored `hp`, version 2 calls it `health`
+/// registry.add_component_migration::<Health>(1, |mut data| {
+///     let hp = data["hp"].take();
+///     data["health"] = hp;
+///     Ok(data)
+/// });
+/// ```
+#[cfg(feature = "serde")]
+pub struct SnapshotRegistry{
+    components: Vec<ComponentFormat>,
+    resources: Vec<ResourceFormat>
+}
+#[cfg(feature = "serde")]
+impl SnapshotRegistry{
+    /// Save the given Component in Snapshots under it's `ID`
+    pub fn register_component<C: Component + Serialize + DeserializeOwned>(&mut self){
+        if self.components.iter().any(|format| format.id == C::ID){
+            return
+        }
+        self.components.push(ComponentFormat{
+            id: C::ID,
+            versioning: Versioning::new(),
+            ids: |world| {
+                let mut query = <Query<'_, (&C,)> as SystemData>::fetch(world);
+                query.iter().map(|(id, _)| id).collect()
+            },
+            encode: |world, id| {
+                world.fetch::<C>().get(&id).map(serde_json::to_value)
+            },
+            decode: |data| {
+                let comp: C = serde_json::from_value(data)?;
+                Ok(Box::new(move |world: &World, id| {
+                    world.fetch_mut::<C>().insert(id, comp);
+                }))
+            },
+        });
+    }
+    /// Save the given Resource in Snapshots under it's `ID`
+    /// 
+    /// Restoring a Snapshot overwrites the Resource
+    pub fn register_resource<R: Resource + Serialize + DeserializeOwned>(&mut self){
+        if self.resources.iter().any(|format| format.id == R::ID){
+            return
+        }
+        self.resources.push(ResourceFormat{
+            id: R::ID,
+            versioning: Versioning::new(),
+            encode: |world| serde_json::to_value(&*world.fetch_res::<R>()),
+            decode: |data| {
+                let res: R = serde_json::from_value(data)?;
+                Ok(Box::new(move |world: &World| {
+                    *world.fetch_res_mut::<R>() = res;
+                }))
+            },
+        });
+    }
+    /// Stop saving the given Component
+    pub fn unregister_component<C: Component>(&mut self){
+        self.components.retain(|format| format.id != C::ID);
+    }
+    /// Stop saving the given Resource
+    pub fn unregister_resource<R: Resource>(&mut self){
+        self.resources.retain(|format| format.id != R::ID);
+    }
+    /// Get the version the Component is saved with, if it's registered
+    pub fn component_version<C: Component>(&self) -> Option<u32>{
+        self.components.iter().find(|format| format.id == C::ID).map(|format| format.versioning.version)
+    }
+    /// Get the version the Resource is saved with, if it's registered
+    pub fn resource_version<R: Resource>(&self) -> Option<u32>{
+        self.resources.iter().find(|format| format.id == R::ID).map(|format| format.versioning.version)
+    }
+    /// Upgrade saved data of the Component from version `from` to `from + 1`
+    /// 
+    /// The Component's version becomes `from + 1` if it was lower.
+    /// Does nothing if the Component is not registered
+    pub fn add_component_migration<C: Component>(&mut self, from: u32, migration: Migration){
+        if let Some(format) = self.components.iter_mut().find(|format| format.id == C::ID){
+            format.versioning.add_migration(from, migration);
+        }
+    }
+    /// Upgrade saved data of the Resource from version `from` to `from + 1`
+    /// 
+    /// The Resource's version becomes `from + 1` if it was lower.
+    /// Does nothing if the Resource is not registered
+    pub fn add_resource_migration<R: Resource>(&mut self, from: u32, migration: Migration){
+        if let Some(format) = self.resources.iter_mut().find(|format| format.id == R::ID){
+            format.versioning.add_migration(from, migration);
+        }
+    }
+    /// Save every registered Component of every Entity, and every registered Resource
+    /// 
+    /// See `WorldSnapshot::capture`
+    pub fn capture(&self, world: &World) -> Result<WorldSnapshot, SnapshotError>{
+        let mut snapshot = WorldSnapshot::new();
+        let mut entities: BTreeMap<usize, EntityRecord> = BTreeMap::new();
+
+        for format in self.components.iter(){
+            snapshot.component_versions.insert(format.id.to_string(), format.versioning.version);
+            for id in (format.ids)(world){
+                let data = match (format.encode)(world, id){
+                    Some(data) => data.map_err(|err| SnapshotError::Serde{id: format.id.to_string(), message: err.to_string()})?,
+                    None => continue,
+                };
+                entities.entry(id).or_insert_with(|| EntityRecord{id, components: BTreeMap::new()})
+                    .components.insert(format.id.to_string(), data);
+            }
+        }
+        snapshot.entities = entities.into_values().collect();
+
+        for format in self.resources.iter(){
+            let data = (format.encode)(world).map_err(|err| SnapshotError::Serde{id: format.id.to_string(), message: err.to_string()})?;
+            snapshot.resource_versions.insert(format.id.to_string(), format.versioning.version);
+            snapshot.resources.insert(format.id.to_string(), data);
+        }
+        Ok(snapshot)
+    }
+    /// Upgrade everything in the Snapshot to the currently registered versions
+    /// 
+    /// Fails if the Snapshot has anything that is not registered,
+    /// or was saved with a newer version than the registered one.
+    /// A failed Snapshot may be left partly migrated
+    pub fn migrate(&self, snapshot: &mut WorldSnapshot) -> Result<(), SnapshotError>{
+        if snapshot.format > SNAPSHOT_FORMAT{
+            return Err(SnapshotError::UnsupportedFormat{found: snapshot.format, supported: SNAPSHOT_FORMAT})
+        }
+        for entity in snapshot.entities.iter_mut(){
+            for (id, data) in entity.components.iter_mut(){
+                let format = self.components.iter().find(|format| format.id == id)
+                    .ok_or_else(|| SnapshotError::UnknownComponent(id.clone()))?;
+                let found = snapshot.component_versions.get(id).copied().unwrap_or(1);
+                *data = format.versioning.migrate(id, found, data.take())?;
+            }
+        }
+        for (id, data) in snapshot.resources.iter_mut(){
+            let format = self.resources.iter().find(|format| format.id == id)
+                .ok_or_else(|| SnapshotError::UnknownResource(id.clone()))?;
+            let found = snapshot.resource_versions.get(id).copied().unwrap_or(1);
+            *data = format.versioning.migrate(id, found, data.take())?;
+        }
+
+        for format in self.components.iter(){
+            snapshot.component_versions.insert(format.id.to_string(), format.versioning.version);
+        }
+        for format in self.resources.iter(){
+            snapshot.resource_versions.insert(format.id.to_string(), format.versioning.version);
+        }
+        Ok(())
+    }
+    /// Migrate the Snapshot and turn it back into Components and Resources
+    pub(crate) fn decode(&self, mut snapshot: WorldSnapshot) -> Result<DecodedSnapshot, SnapshotError>{
+        self.migrate(&mut snapshot)?;
+
+        let mut entities = Vec::new();
+        for entity in snapshot.entities{
+            let mut inserters = Vec::new();
+            for (id, data) in entity.components{
+                let format = self.components.iter().find(|format| format.id == id)
+                    .ok_or_else(|| SnapshotError::UnknownComponent(id.clone()))?;
+                inserters.push((format.decode)(data).map_err(|err| SnapshotError::Serde{id, message: err.to_string()})?);
+            }
+            entities.push((entity.id, inserters));
+        }
+
+        let mut resources = Vec::new();
+        for (id, data) in snapshot.resources{
+            let format = self.resources.iter().find(|format| format.id == id)
+                .ok_or_else(|| SnapshotError::UnknownResource(id.clone()))?;
+            resources.push((format.decode)(data).map_err(|err| SnapshotError::Serde{id, message: err.to_string()})?);
+        }
+        Ok(DecodedSnapshot{entities, resources})
+    }
+}
+#[cfg(feature = "serde")]
+impl Resource for SnapshotRegistry{
+    const ID: &'static str = "SnapshotRegistry";
+
+    fn new() -> Self {
+        let mut registry = Self{
+            components: Vec::new(),
+            resources: Vec::new(),
+        };
+        registry.register_component::<comp::CMDSprite>();
+        registry.register_component::<comp::Persistent>();
+        registry.register_component::<comp::PlayerController>();
+        registry.register_component::<comp::SceneMember>();
+        registry.register_component::<comp::Tags>();
+        registry.register_component::<comp::Transform2D>();
+        registry.register_component::<comp::Transform3D>();
+        registry
+    }
+}
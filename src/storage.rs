@@ -1,4 +1,5 @@
 use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
 
 use super::*;
 
@@ -161,4 +162,87 @@ impl<C: Component> Storage<C> for DenseVecStorage<C>{
     fn len(&self) -> usize {
         self.inner.len()
     }
+}
+
+/// What happened to an Entity's Component since the Storage's changes were last taken
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageChange{
+    /// The Entity didn't have the Component before
+    Added,
+    /// The Entity had the Component before and doesn't anymore
+    Removed,
+    /// The Entity had the Component before and still has it, but it's been put in anew
+    Replaced,
+}
+
+/// # Change Log trait
+/// Storages that remember which Entities had their Component inserted or removed
+/// 
+/// Required for `TrackedComponent`s, so their Events get sent no matter who changed the Storage
+pub trait ChangeLog{
+    /// Take the changes made since the last call, in the order the Entities were first touched
+    /// 
+    /// Entities that ended up like they were before, like inserting and then removing, are left out
+    fn take_changes(&mut self) -> Vec<(usize, StorageChange)>;
+}
+
+/// # Tracked Storage
+/// Wraps another Storage and logs every insert and remove for `ChangeLog`
+/// 
+/// Changes made in place through `get_mut` are not logged, the Entity still has the same Component
+pub struct TrackedStorage<C: Component, S: Storage<C> = HashMapStorage<C>>{
+    inner: S,
+    /// Touched Entities, with whether they had the Component before being touched
+    touched: Vec<(usize, bool)>,
+    _marker: PhantomData<C>
+}
+impl<C: Component, S: Storage<C>> TrackedStorage<C, S>{
+    fn touch(&mut self, id: usize){
+        if !self.touched.iter().any(|(touched, _)| *touched == id){
+            let had = self.inner.get(&id).is_some();
+            self.touched.push((id, had));
+        }
+    }
+}
+impl<C: Component, S: Storage<C>> Storage<C> for TrackedStorage<C, S>{
+    fn new() -> Self {
+        Self{
+            inner: S::new(),
+            touched: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    fn insert(&mut self, id: usize, comp: C) {
+        self.touch(id);
+        self.inner.insert(id, comp);
+    }
+    fn remove(&mut self, id: &usize) {
+        self.touch(*id);
+        self.inner.remove(id);
+    }
+
+    fn get(&self, id: &usize) -> Option<&C> {
+        self.inner.get(id)
+    }
+    fn get_mut(&mut self, id: &usize) -> Option<&mut C> {
+        self.inner.get_mut(id)
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<C: Component, S: Storage<C>> ChangeLog for TrackedStorage<C, S>{
+    fn take_changes(&mut self) -> Vec<(usize, StorageChange)> {
+        let touched = std::mem::take(&mut self.touched);
+        touched.into_iter().filter_map(|(id, had)| {
+            match (had, self.inner.get(&id).is_some()){
+                (false, true) => Some((id, StorageChange::Added)),
+                (true, false) => Some((id, StorageChange::Removed)),
+                (true, true) => Some((id, StorageChange::Replaced)),
+                (false, false) => None,
+            }
+        }).collect()
+    }
 }
\ No newline at end of file
@@ -1,5 +1,10 @@
+use std::collections::HashSet;
+
 use super::*;
+use comp::*;
+use events::{TweenCompleted, TweenLooped};
 use resources::*;
+use types::*;
 
 /// # Command Line Input Handler
 /// Acquires the current pressed key from the Command Line
@@ -29,4 +34,308 @@ impl System for CMDInputHandler{
             data.reset();
         }
     }
+}
+
+/// # Frame Timer
+/// Measures how long the previous frame took and stores it in `FrameTime`
+/// 
+/// Runs before other Core Systems, so they all see the same frame time
+pub struct FrameTimer;
+impl System for FrameTimer{
+    type Data<'a> = &'a mut FrameTime;
+    const ID: &'static str = "FrameTimer";
+    const TYPE: SystemType = SystemType::Preprocessor;
+
+    fn new() -> Self { Self }
+
+    fn execute(&mut self, mut data: Request<'_, Self::Data<'_>>) {
+        data.tick();
+    }
+}
+
+/// # Tag Indexer
+/// Brings `TagIndex` up to date with `Tags` that were changed directly instead of through Commands
+/// 
+/// Also drops Entities that no longer have `Tags` from the index, however they lost them.
+/// Runs before Logic Systems, so lookups in the index see last frame's changes
+pub struct TagIndexer;
+impl System for TagIndexer{
+    type Data<'a> = (Query<'a, (&'a mut Tags,)>, &'a mut TagIndex);
+    const ID: &'static str = "TagIndexer";
+    const TYPE: SystemType = SystemType::Preprocessor;
+
+    fn new() -> Self { Self }
+
+    fn execute(&mut self, mut data: Request<'_, Self::Data<'_>>) {
+        let (tags, index) = &mut *data;
+
+        let mut tagged = HashSet::new();
+        for (id, (tags,)) in tags.iter(){
+            tagged.insert(id);
+            if tags.is_dirty(){
+                index.sync(id, Some(tags));
+                tags.mark_clean();
+            }
+        }
+        index.retain(|id| tagged.contains(&id));
+    }
+}
+
+/// # Scheduled Command Runner
+/// Sends off Commands from `CommandScheduler` once they're due
+/// 
+/// Advances the Scheduler's clock by `FrameTime`
+pub struct ScheduledCommandRunner;
+impl System for ScheduledCommandRunner{
+    type Data<'a> = (&'a FrameTime, &'a mut CommandScheduler, CommandWriter<'a>);
+    const ID: &'static str = "ScheduledCommandRunner";
+    const TYPE: SystemType = SystemType::Preprocessor;
+
+    fn new() -> Self { Self }
+
+    fn execute(&mut self, mut data: Request<'_, Self::Data<'_>>) {
+        let (time, scheduler, commands) = &mut *data;
+
+        for command in scheduler.advance(time.delta()){
+            commands.send(BoxedCommand(command));
+        }
+    }
+}
+
+/// Lets an already boxed Command be sent again
+struct BoxedCommand(Box<dyn Command>);
+impl Command for BoxedCommand{
+    fn execute(&mut self, world: &mut World) {
+        self.0.execute(world);
+    }
+}
+
+/// # Change Announcer
+/// Announces Components added and removed straight through their Storages this frame
+/// 
+/// Covers the Components listed in `ComponentTracker` Resource, changes made by Core Commands are announced by them right away
+pub struct ChangeAnnouncer;
+impl System for ChangeAnnouncer{
+    type Data<'a> = CommandWriter<'a>;
+    const ID: &'static str = "ChangeAnnouncer";
+    const TYPE: SystemType = SystemType::Postprocessor;
+
+    fn new() -> Self { Self }
+
+    fn execute(&mut self, mut data: Request<'_, Self::Data<'_>>) {
+        data.send(AnnounceChanges);
+    }
+}
+
+/// Sends `ChangeAnnouncer`'s Events, as Systems can't look up Tokens
+struct AnnounceChanges;
+impl Command for AnnounceChanges{
+    fn execute(&mut self, world: &mut World) {
+        world.fetch_res::<ComponentTracker>().announce(world);
+    }
+}
+
+/// # Tween Runner
+/// Advances every `Tween` and writes it's current value to the Entity's Components
+/// 
+/// Runs before Logic Systems so they see this frame's animated values and can still override them.
+/// Advances by `FrameTime`, finished `Tween`s get removed after sending `TweenCompleted`
+pub struct TweenRunner;
+impl System for TweenRunner{
+    type Data<'a> = (
+        &'a FrameTime,
+        Query<'a, (&'a mut Tween,)>,
+        FetchMut<'a, Transform2D>,
+        FetchMut<'a, Transform3D>,
+        FetchMut<'a, CMDSprite>,
+        CommandWriter<'a>
+    );
+    const ID: &'static str = "TweenRunner";
+    const TYPE: SystemType = SystemType::Preprocessor;
+
+    fn new() -> Self { Self }
+
+    fn execute(&mut self, mut data: Request<'_, Self::Data<'_>>) {
+        let (time, tweens, transforms_2d, transforms_3d, sprites, commands) = &mut *data;
+        let delta = time.delta();
+
+        for (id, (tween,)) in tweens.iter(){
+            if tween.is_finished(){
+                continue;
+            }
+            let state = tween.advance(delta);
+
+            if let Some(transform) = transforms_2d.get_mut(&id){
+                tween.apply_2d(transform);
+            }
+            if let Some(transform) = transforms_3d.get_mut(&id){
+                tween.apply_3d(transform);
+            }
+            if let Some(sprite) = sprites.get_mut(&id){
+                tween.apply_sprite(sprite);
+            }
+
+            if state != TweenState::Running{
+                commands.send(ReportTween{id, state});
+            }
+        }
+    }
+}
+
+/// Sends `TweenRunner`'s Events, as Systems can't look up Tokens
+struct ReportTween{
+    id: usize,
+    state: TweenState
+}
+impl Command for ReportTween{
+    fn execute(&mut self, world: &mut World) {
+        let token = match world.get_token(self.id){
+            Some(token) => token,
+            None => return,
+        };
+        if self.state == TweenState::Looped{
+            return world.get_event_writer::<TweenLooped>().send(TweenLooped(token))
+        }
+
+        // Someone could've put a new Tween in since, leave that one alone
+        {
+            let mut tweens = world.fetch_mut::<Tween>();
+            if !tweens.get(&self.id).is_some_and(|tween| tween.is_finished()){
+                return
+            }
+            tweens.remove(&self.id);
+        }
+        commands::announce::<Tween>(world);
+        world.get_event_writer::<TweenCompleted>().send(TweenCompleted(token));
+    }
+}
+
+/// # 2D Transform Propagator
+/// Calculates `GlobalTransform2D` of every Entity with `Transform2D`
+/// 
+/// Walks each hierarchy from it's root down, so parents are always resolved before their children.
+/// Entities in the hierarchy without `Transform2D` pass their parent's transform down as-is,
+/// roots without one count as sitting at the origin
+/// 
+/// Adds `GlobalTransform2D` to Entities that don't have it yet,
+/// and removes it from Entities that lost their `Transform2D` since the last frame
+pub struct Transform2DPropagator{
+    /// Entities given a `GlobalTransform2D` on the last frame
+    written: HashSet<usize>
+}
+impl System for Transform2DPropagator{
+    type Data<'a> = (
+        Query<'a, (&'a Transform2D,), Without<Parent>>,
+        Query<'a, (&'a Children,), Without<Parent>>,
+        Fetch<'a, Transform2D>,
+        Fetch<'a, Children>,
+        FetchMut<'a, GlobalTransform2D>
+    );
+    const ID: &'static str = "Transform2DPropagator";
+    const TYPE: SystemType = SystemType::Postprocessor;
+
+    fn new() -> Self {
+        Self{
+            written: HashSet::new(),
+        }
+    }
+
+    fn execute(&mut self, mut data: Request<'_, Self::Data<'_>>) {
+        let (roots, parents, locals, children, globals) = &mut *data;
+
+        let mut stack: Vec<(usize, GlobalTransform2D)> = roots.iter().map(|(id, _)| id)
+            .chain(parents.iter().map(|(id, _)| id).filter(|id| locals.get(id).is_none()))
+            .map(|id| (id, GlobalTransform2D::IDENTITY))
+            .collect();
+
+        let mut written = HashSet::new();
+        while let Some((id, parent)) = stack.pop(){
+            let global = match locals.get(&id){
+                Some(local) => {
+                    let global = parent.then(local);
+                    match globals.get_mut(&id){
+                        Some(current) => *current = global,
+                        None => globals.insert(id, global),
+                    }
+                    written.insert(id);
+                    global
+                }
+                None => parent,
+            };
+
+            if let Some(kids) = children.get(&id){
+                stack.extend(kids.iter().map(|child| (child.id(), global)));
+            }
+        }
+
+        for id in self.written.difference(&written){
+            globals.remove(id);
+        }
+        self.written = written;
+    }
+}
+
+/// # 3D Transform Propagator
+/// Calculates `GlobalTransform3D` of every Entity with `Transform3D`
+/// 
+/// Walks each hierarchy from it's root down, so parents are always resolved before their children.
+/// Entities in the hierarchy without `Transform3D` pass their parent's transform down as-is,
+/// roots without one count as sitting at the origin
+/// 
+/// Adds `GlobalTransform3D` to Entities that don't have it yet,
+/// and removes it from Entities that lost their `Transform3D` since the last frame
+pub struct Transform3DPropagator{
+    /// Entities given a `GlobalTransform3D` on the last frame
+    written: HashSet<usize>
+}
+impl System for Transform3DPropagator{
+    type Data<'a> = (
+        Query<'a, (&'a Transform3D,), Without<Parent>>,
+        Query<'a, (&'a Children,), Without<Parent>>,
+        Fetch<'a, Transform3D>,
+        Fetch<'a, Children>,
+        FetchMut<'a, GlobalTransform3D>
+    );
+    const ID: &'static str = "Transform3DPropagator";
+    const TYPE: SystemType = SystemType::Postprocessor;
+
+    fn new() -> Self {
+        Self{
+            written: HashSet::new(),
+        }
+    }
+
+    fn execute(&mut self, mut data: Request<'_, Self::Data<'_>>) {
+        let (roots, parents, locals, children, globals) = &mut *data;
+
+        let mut stack: Vec<(usize, GlobalTransform3D)> = roots.iter().map(|(id, _)| id)
+            .chain(parents.iter().map(|(id, _)| id).filter(|id| locals.get(id).is_none()))
+            .map(|id| (id, GlobalTransform3D::IDENTITY))
+            .collect();
+
+        let mut written = HashSet::new();
+        while let Some((id, parent)) = stack.pop(){
+            let global = match locals.get(&id){
+                Some(local) => {
+                    let global = parent.then(local);
+                    match globals.get_mut(&id){
+                        Some(current) => *current = global,
+                        None => globals.insert(id, global),
+                    }
+                    written.insert(id);
+                    global
+                }
+                None => parent,
+            };
+
+            if let Some(kids) = children.get(&id){
+                stack.extend(kids.iter().map(|child| (child.id(), global)));
+            }
+        }
+
+        for id in self.written.difference(&written){
+            globals.remove(id);
+        }
+        self.written = written;
+    }
 }
\ No newline at end of file
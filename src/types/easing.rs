@@ -0,0 +1,97 @@
+use std::f32::consts::PI;
+
+/// # Easing curve
+/// Reshapes linear progress into something that looks more natural when animated
+///
+/// `In` curves start slow, `Out` curves end slow and `InOut` do both.
+/// All curves go from 0 at `t = 0` to 1 at `t = 1`, but `Elastic` and `Back` overshoot along the way
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Easing{
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    /// Winds up and springs past the end like a rubber band
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    /// Bounces off the end like a dropped ball
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+    /// Pulls back a bit before going
+    BackIn,
+    BackOut,
+    BackInOut,
+}
+impl Easing{
+    /// Apply the curve to `t`, which gets clamped to `0..=1`
+    pub fn apply(self, t: f32) -> f32{
+        let t = t.clamp(0.0, 1.0);
+        match self{
+            Easing::Linear => t,
+
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => in_out(t, |t| t * t),
+
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => in_out(t, |t| t * t * t),
+
+            Easing::ElasticIn => elastic_in(t),
+            Easing::ElasticOut => 1.0 - elastic_in(1.0 - t),
+            Easing::ElasticInOut => in_out(t, elastic_in),
+
+            Easing::BounceIn => 1.0 - bounce_out(1.0 - t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => in_out(t, |t| 1.0 - bounce_out(1.0 - t)),
+
+            Easing::BackIn => back_in(t),
+            Easing::BackOut => 1.0 - back_in(1.0 - t),
+            Easing::BackInOut => in_out(t, back_in),
+        }
+    }
+}
+
+/// Runs an `In` curve over the first half and it's mirror over the second
+fn in_out(t: f32, ease_in: impl Fn(f32) -> f32) -> f32{
+    if t < 0.5{
+        ease_in(t * 2.0) / 2.0
+    } else {
+        1.0 - ease_in((1.0 - t) * 2.0) / 2.0
+    }
+}
+
+fn elastic_in(t: f32) -> f32{
+    if t == 0.0 || t == 1.0{
+        return t
+    }
+    -(2.0f32).powf(10.0 * t - 10.0) * ((t * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin()
+}
+
+fn bounce_out(t: f32) -> f32{
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D{
+        N * t * t
+    } else if t < 2.0 / D{
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D{
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+fn back_in(t: f32) -> f32{
+    const OVERSHOOT: f32 = 1.70158;
+    (OVERSHOOT + 1.0) * t * t * t - OVERSHOOT * t * t
+}
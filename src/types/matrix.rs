@@ -0,0 +1,607 @@
+use std::ops::{Mul, MulAssign};
+
+use super::{Vector2, Vector3};
+
+/// Returns the biggest absolute entry, used to scale singularity checks to the Matrix's size
+fn largest<const N: usize, const M: usize>(rows: &[[f32; M]; N]) -> f32{
+    rows.iter().flatten().fold(0.0, |max, cell| max.max(cell.abs()))
+}
+
+/// # 3x3 Matrix
+/// Row-major, used for 3D rotations and scaling
+///
+/// Multiplying `a * b` gives a matrix that applies `b` first, then `a`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Mat3{
+    pub rows: [[f32; 3]; 3]
+}
+impl Mat3{
+    pub const IDENTITY: Self = Self{
+        rows: [
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0]
+        ]
+    };
+
+    /// Create a Matrix from it's rows
+    pub fn from_rows(rows: [[f32; 3]; 3]) -> Self{
+        Self{rows}
+    }
+    /// Create a Matrix from it's columns, which are where the X, Y and Z axis end up
+    pub fn from_cols(x: Vector3, y: Vector3, z: Vector3) -> Self{
+        Self{
+            rows: [
+                [x.x, y.x, z.x],
+                [x.y, y.y, z.y],
+                [x.z, y.z, z.z]
+            ]
+        }
+    }
+    /// Create a scaling Matrix
+    pub fn from_scale(scale: Vector3) -> Self{
+        Self{
+            rows: [
+                [scale.x, 0.0, 0.0],
+                [0.0, scale.y, 0.0],
+                [0.0, 0.0, scale.z]
+            ]
+        }
+    }
+    /// Create a rotation Matrix from XYZ Euler angles in Radians
+    ///
+    /// Rotations are applied around X first, then Y, then Z, all around the world axis.
+    /// Z is up, so `rot.z` is the heading
+    pub fn from_euler(rot: Vector3) -> Self{
+        let (sx, cx) = rot.x.sin_cos();
+        let (sy, cy) = rot.y.sin_cos();
+        let (sz, cz) = rot.z.sin_cos();
+        Self{
+            rows: [
+                [cy * cz, sx * sy * cz - cx * sz, cx * sy * cz + sx * sz],
+                [cy * sz, sx * sy * sz + cx * cz, cx * sy * sz - sx * cz],
+                [-sy,     sx * cy,                cx * cy]
+            ]
+        }
+    }
+    /// Create a rotation Matrix from a Quaternion
+    pub fn from_quat(quat: Quat) -> Self{
+        let Quat{x, y, z, w} = quat.normalize();
+        Self{
+            rows: [
+                [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z),       2.0 * (x * z + w * y)],
+                [2.0 * (x * y + w * z),       1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x)],
+                [2.0 * (x * z - w * y),       2.0 * (y * z + w * x),       1.0 - 2.0 * (x * x + y * y)]
+            ]
+        }
+    }
+    /// Extract XYZ Euler angles from a rotation Matrix, in the same order as `from_euler`
+    ///
+    /// On gimbal lock (Y at ±90°) X and Z rotate around the same axis, so it all goes into Z
+    pub fn to_euler(self) -> Vector3{
+        let m = self.rows;
+        let cy = m[0][0].hypot(m[1][0]);
+        let y = (-m[2][0]).atan2(cy);
+        if cy < 1e-6{
+            return Vector3::new(0.0, y, (-m[0][1]).atan2(m[1][1]))
+        }
+        Vector3::new(m[2][1].atan2(m[2][2]), y, m[1][0].atan2(m[0][0]))
+    }
+    /// Returns the column `index`, which is where that axis ends up
+    pub fn col(self, index: usize) -> Vector3{
+        Vector3::new(self.rows[0][index], self.rows[1][index], self.rows[2][index])
+    }
+    /// Returns the Matrix flipped around it's diagonal
+    ///
+    /// For pure rotation matrices this is also the inverse
+    pub fn transpose(self) -> Self{
+        let m = self.rows;
+        Self{
+            rows: [
+                [m[0][0], m[1][0], m[2][0]],
+                [m[0][1], m[1][1], m[2][1]],
+                [m[0][2], m[1][2], m[2][2]]
+            ]
+        }
+    }
+    pub fn determinant(self) -> f32{
+        let m = self.rows;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+    /// Returns the inverse of the Matrix, or `None` if it squashes space flat (zero determinant)
+    ///
+    /// The determinant is compared against the size of the Matrix's entries,
+    /// so tiny but well-shaped Matrices can still be inverted
+    pub fn inverse(self) -> Option<Self>{
+        let det = self.determinant();
+        if det.abs() <= f32::EPSILON * largest(&self.rows).powi(3){
+            return None
+        }
+        let m = self.rows;
+        let inv = 1.0 / det;
+        Some(Self{
+            rows: [
+                [
+                    (m[1][1] * m[2][2] - m[1][2] * m[2][1]) * inv,
+                    (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv,
+                    (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv
+                ],
+                [
+                    (m[1][2] * m[2][0] - m[1][0] * m[2][2]) * inv,
+                    (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv,
+                    (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv
+                ],
+                [
+                    (m[1][0] * m[2][1] - m[1][1] * m[2][0]) * inv,
+                    (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv,
+                    (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv
+                ]
+            ]
+        })
+    }
+}
+impl Default for Mat3{
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+impl Mul for Mat3{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut out = [[0.0; 3]; 3];
+        for (row, out_row) in out.iter_mut().enumerate(){
+            for (col, cell) in out_row.iter_mut().enumerate(){
+                *cell = self.rows[row][0] * rhs.rows[0][col]
+                    + self.rows[row][1] * rhs.rows[1][col]
+                    + self.rows[row][2] * rhs.rows[2][col];
+            }
+        }
+        Self{rows: out}
+    }
+}
+impl MulAssign for Mat3{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+impl Mul<Vector3> for Mat3{
+    type Output = Vector3;
+
+    fn mul(self, rhs: Vector3) -> Self::Output {
+        let m = self.rows;
+        Vector3::new(
+            m[0][0] * rhs.x + m[0][1] * rhs.y + m[0][2] * rhs.z,
+            m[1][0] * rhs.x + m[1][1] * rhs.y + m[1][2] * rhs.z,
+            m[2][0] * rhs.x + m[2][1] * rhs.y + m[2][2] * rhs.z
+        )
+    }
+}
+
+/// # 4x4 Matrix
+/// Row-major affine transform, holding translation in the last column
+///
+/// Multiplying `a * b` gives a matrix that applies `b` first, then `a`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Mat4{
+    pub rows: [[f32; 4]; 4]
+}
+impl Mat4{
+    pub const IDENTITY: Self = Self{
+        rows: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0]
+        ]
+    };
+
+    /// Create a Matrix from it's rows
+    pub fn from_rows(rows: [[f32; 4]; 4]) -> Self{
+        Self{rows}
+    }
+    /// Create a translation Matrix
+    pub fn from_translation(loc: Vector3) -> Self{
+        let mut out = Self::IDENTITY;
+        out.rows[0][3] = loc.x;
+        out.rows[1][3] = loc.y;
+        out.rows[2][3] = loc.z;
+        out
+    }
+    /// Create a Matrix that applies `linear` with no translation
+    pub fn from_mat3(linear: Mat3) -> Self{
+        Self::from_parts(linear, Vector3::new(0.0, 0.0, 0.0))
+    }
+    /// Create a Matrix that applies `linear` first, then moves by `loc`
+    pub fn from_parts(linear: Mat3, loc: Vector3) -> Self{
+        let m = linear.rows;
+        Self{
+            rows: [
+                [m[0][0], m[0][1], m[0][2], loc.x],
+                [m[1][0], m[1][1], m[1][2], loc.y],
+                [m[2][0], m[2][1], m[2][2], loc.z],
+                [0.0,     0.0,     0.0,     1.0]
+            ]
+        }
+    }
+    /// Create a Matrix that scales, then rotates, then translates
+    pub fn from_trs(loc: Vector3, rot: Quat, scale: Vector3) -> Self{
+        Self::from_parts(Mat3::from_quat(rot) * Mat3::from_scale(scale), loc)
+    }
+    /// Returns the upper-left 3x3 part, which holds rotation and scale
+    pub fn linear(self) -> Mat3{
+        let m = self.rows;
+        Mat3{
+            rows: [
+                [m[0][0], m[0][1], m[0][2]],
+                [m[1][0], m[1][1], m[1][2]],
+                [m[2][0], m[2][1], m[2][2]]
+            ]
+        }
+    }
+    /// Returns the translation part
+    pub fn translation(self) -> Vector3{
+        Vector3::new(self.rows[0][3], self.rows[1][3], self.rows[2][3])
+    }
+    /// Transform a point, translation included
+    pub fn transform_point(self, point: Vector3) -> Vector3{
+        self.linear() * point + self.translation()
+    }
+    /// Transform a direction, translation is ignored
+    pub fn transform_vector(self, vector: Vector3) -> Vector3{
+        self.linear() * vector
+    }
+    /// Returns the Matrix flipped around it's diagonal
+    pub fn transpose(self) -> Self{
+        let mut out = [[0.0; 4]; 4];
+        for (row, out_row) in out.iter_mut().enumerate(){
+            for (col, cell) in out_row.iter_mut().enumerate(){
+                *cell = self.rows[col][row];
+            }
+        }
+        Self{rows: out}
+    }
+    /// Returns the inverse of the Matrix, or `None` if it can't be inverted
+    ///
+    /// Pivots are compared against the size of the Matrix's entries,
+    /// so tiny but well-shaped Matrices can still be inverted
+    pub fn inverse(self) -> Option<Self>{
+        // Gauss-Jordan elimination with partial pivoting
+        let mut m = self.rows;
+        let mut inv = Self::IDENTITY.rows;
+        let tolerance = f32::EPSILON * largest(&self.rows);
+        for col in 0..4{
+            let pivot = (col..4)
+                .max_by(|a, b| m[*a][col].abs().total_cmp(&m[*b][col].abs()))
+                .unwrap_or(col);
+            if m[pivot][col].abs() <= tolerance{
+                return None
+            }
+            m.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1.0 / m[col][col];
+            for i in 0..4{
+                m[col][i] *= scale;
+                inv[col][i] *= scale;
+            }
+            for row in 0..4{
+                if row == col{
+                    continue;
+                }
+                let factor = m[row][col];
+                for i in 0..4{
+                    m[row][i] -= factor * m[col][i];
+                    inv[row][i] -= factor * inv[col][i];
+                }
+            }
+        }
+        Some(Self{rows: inv})
+    }
+}
+impl Default for Mat4{
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+impl Mul for Mat4{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut out = [[0.0; 4]; 4];
+        for (row, out_row) in out.iter_mut().enumerate(){
+            for (col, cell) in out_row.iter_mut().enumerate(){
+                *cell = (0..4).map(|i| self.rows[row][i] * rhs.rows[i][col]).sum();
+            }
+        }
+        Self{rows: out}
+    }
+}
+impl MulAssign for Mat4{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+
+/// # Quaternion
+/// Rotation without gimbal lock, composes and interpolates better than Euler angles
+///
+/// Multiplying `a * b` gives a rotation that applies `b` first, then `a`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Quat{
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32
+}
+impl Quat{
+    pub const IDENTITY: Self = Self{x: 0.0, y: 0.0, z: 0.0, w: 1.0};
+
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self{
+        Self{x, y, z, w}
+    }
+    /// Create a rotation of `angle` Radians around `axis`
+    pub fn from_axis_angle(axis: Vector3, angle: f32) -> Self{
+        let axis = axis.normalize();
+        let (s, c) = (angle * 0.5).sin_cos();
+        Self{x: axis.x * s, y: axis.y * s, z: axis.z * s, w: c}
+    }
+    /// Create a rotation from XYZ Euler angles in Radians, in the same order as `Mat3::from_euler`
+    pub fn from_euler(rot: Vector3) -> Self{
+        let x = Self::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), rot.x);
+        let y = Self::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), rot.y);
+        let z = Self::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), rot.z);
+        z * y * x
+    }
+    /// Create a rotation from a rotation Matrix
+    pub fn from_mat3(mat: Mat3) -> Self{
+        let m = mat.rows;
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        let out = if trace > 0.0{
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Self::new((m[2][1] - m[1][2]) / s, (m[0][2] - m[2][0]) / s, (m[1][0] - m[0][1]) / s, 0.25 * s)
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2]{
+            let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+            Self::new(0.25 * s, (m[0][1] + m[1][0]) / s, (m[0][2] + m[2][0]) / s, (m[2][1] - m[1][2]) / s)
+        } else if m[1][1] > m[2][2]{
+            let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+            Self::new((m[0][1] + m[1][0]) / s, 0.25 * s, (m[1][2] + m[2][1]) / s, (m[0][2] - m[2][0]) / s)
+        } else {
+            let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+            Self::new((m[0][2] + m[2][0]) / s, (m[1][2] + m[2][1]) / s, 0.25 * s, (m[1][0] - m[0][1]) / s)
+        };
+        out.normalize()
+    }
+    /// Extract XYZ Euler angles, in the same order as `from_euler`
+    pub fn to_euler(self) -> Vector3{
+        Mat3::from_quat(self).to_euler()
+    }
+    pub fn to_mat3(self) -> Mat3{
+        Mat3::from_quat(self)
+    }
+    pub fn dot(self, other: Self) -> f32{
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+    pub fn magnitude(self) -> f32{
+        self.dot(self).sqrt()
+    }
+    /// Returns self normalized to magnitude of 1, rotation Quaternions should always be
+    pub fn normalize(self) -> Self{
+        let mag = self.magnitude();
+        Self::new(self.x / mag, self.y / mag, self.z / mag, self.w / mag)
+    }
+    /// Returns the rotation going the opposite way
+    ///
+    /// Same as the inverse for normalized Quaternions
+    pub fn conjugate(self) -> Self{
+        Self::new(-self.x, -self.y, -self.z, self.w)
+    }
+    pub fn inverse(self) -> Self{
+        let sq = self.dot(self);
+        let c = self.conjugate();
+        Self::new(c.x / sq, c.y / sq, c.z / sq, c.w / sq)
+    }
+    /// Returns a rotation `t` of the way from `self` to `other`, turning at a constant rate the shorter way around
+    pub fn slerp(self, other: Self, t: f32) -> Self{
+        let mut other = other;
+        let mut cos = self.dot(other);
+        // Both `q` and `-q` are the same rotation, flip to take the short way
+        if cos < 0.0{
+            other = Self::new(-other.x, -other.y, -other.z, -other.w);
+            cos = -cos;
+        }
+        let (a, b) = if cos > 0.9995{
+            // Nearly the same rotation, linear is stable and close enough
+            (1.0 - t, t)
+        } else {
+            let angle = cos.acos();
+            let sin = angle.sin();
+            (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin)
+        };
+        Self::new(
+            self.x * a + other.x * b,
+            self.y * a + other.y * b,
+            self.z * a + other.z * b,
+            self.w * a + other.w * b
+        ).normalize()
+    }
+    /// Rotate a Vector
+    pub fn rotate(self, vector: Vector3) -> Vector3{
+        // v' = v + 2w(q x v) + 2q x (q x v), cheaper than q * v * q^-1
+        let q = Vector3::new(self.x, self.y, self.z);
+        let t = q.cross(vector) * 2.0;
+        vector + t * self.w + q.cross(t)
+    }
+}
+impl Default for Quat{
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+impl Mul for Quat{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self{
+            x: self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            y: self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            z: self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+            w: self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+        }
+    }
+}
+impl MulAssign for Quat{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+impl Mul<Vector3> for Quat{
+    type Output = Vector3;
+
+    fn mul(self, rhs: Vector3) -> Self::Output {
+        self.rotate(rhs)
+    }
+}
+
+/// # 2D Affine Matrix
+/// Row-major 2x3 matrix, holding rotation and scale in the first two columns and translation in the last one
+///
+/// Same as a 3x3 homogeneous matrix with an implied `[0, 0, 1]` bottom row, use `to_mat3` if you need that.
+/// Multiplying `a * b` gives a matrix that applies `b` first, then `a`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Affine2{
+    pub rows: [[f32; 3]; 2]
+}
+impl Affine2{
+    pub const IDENTITY: Self = Self{
+        rows: [
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0]
+        ]
+    };
+
+    /// Create a Matrix from it's rows
+    pub fn from_rows(rows: [[f32; 3]; 2]) -> Self{
+        Self{rows}
+    }
+    /// Create a translation Matrix
+    pub fn from_translation(loc: Vector2) -> Self{
+        Self{
+            rows: [
+                [1.0, 0.0, loc.x],
+                [0.0, 1.0, loc.y]
+            ]
+        }
+    }
+    /// Create a counter-clockwise rotation Matrix, `angle` is in Radians
+    pub fn from_rotation(angle: f32) -> Self{
+        let (sin, cos) = angle.sin_cos();
+        Self{
+            rows: [
+                [cos, -sin, 0.0],
+                [sin, cos,  0.0]
+            ]
+        }
+    }
+    /// Create a scaling Matrix
+    pub fn from_scale(scale: Vector2) -> Self{
+        Self{
+            rows: [
+                [scale.x, 0.0, 0.0],
+                [0.0, scale.y, 0.0]
+            ]
+        }
+    }
+    /// Create a Matrix that scales, then rotates, then translates
+    pub fn from_trs(loc: Vector2, rot: f32, scale: Vector2) -> Self{
+        let (sin, cos) = rot.sin_cos();
+        Self{
+            rows: [
+                [cos * scale.x, -sin * scale.y, loc.x],
+                [sin * scale.x, cos * scale.y,  loc.y]
+            ]
+        }
+    }
+    /// Returns the full 3x3 homogeneous Matrix
+    pub fn to_mat3(self) -> Mat3{
+        let m = self.rows;
+        Mat3{
+            rows: [
+                m[0],
+                m[1],
+                [0.0, 0.0, 1.0]
+            ]
+        }
+    }
+    /// Returns the translation part
+    pub fn translation(self) -> Vector2{
+        Vector2::new(self.rows[0][2], self.rows[1][2])
+    }
+    /// Transform a point, translation included
+    pub fn transform_point(self, point: Vector2) -> Vector2{
+        self.transform_vector(point) + self.translation()
+    }
+    /// Transform a direction, translation is ignored
+    pub fn transform_vector(self, vector: Vector2) -> Vector2{
+        let m = self.rows;
+        Vector2::new(
+            m[0][0] * vector.x + m[0][1] * vector.y,
+            m[1][0] * vector.x + m[1][1] * vector.y
+        )
+    }
+    pub fn determinant(self) -> f32{
+        self.rows[0][0] * self.rows[1][1] - self.rows[0][1] * self.rows[1][0]
+    }
+    /// Returns the inverse of the Matrix, or `None` if it squashes space flat (zero determinant)
+    ///
+    /// The determinant is compared against the size of the linear part's entries,
+    /// so tiny but well-shaped Matrices can still be inverted
+    pub fn inverse(self) -> Option<Self>{
+        let det = self.determinant();
+        let linear = [[self.rows[0][0], self.rows[0][1]], [self.rows[1][0], self.rows[1][1]]];
+        if det.abs() <= f32::EPSILON * largest(&linear).powi(2){
+            return None
+        }
+        let m = self.rows;
+        let inv = 1.0 / det;
+        let linear = Self{
+            rows: [
+                [m[1][1] * inv,  -m[0][1] * inv, 0.0],
+                [-m[1][0] * inv, m[0][0] * inv,  0.0]
+            ]
+        };
+        let loc = linear.transform_vector(self.translation()) * -1.0;
+        Some(Self{
+            rows: [
+                [linear.rows[0][0], linear.rows[0][1], loc.x],
+                [linear.rows[1][0], linear.rows[1][1], loc.y]
+            ]
+        })
+    }
+}
+impl Default for Affine2{
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+impl Mul for Affine2{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let (a, b) = (self.rows, rhs.rows);
+        let loc = self.transform_point(rhs.translation());
+        Self{
+            rows: [
+                [a[0][0] * b[0][0] + a[0][1] * b[1][0], a[0][0] * b[0][1] + a[0][1] * b[1][1], loc.x],
+                [a[1][0] * b[0][0] + a[1][1] * b[1][0], a[1][0] * b[0][1] + a[1][1] * b[1][1], loc.y]
+            ]
+        }
+    }
+}
+impl MulAssign for Affine2{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
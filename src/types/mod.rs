@@ -1,11 +1,32 @@
+use std::any::Any;
+use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
 
 use super::*;
-use manufacture_engine::ECS::fetch::Fetch;
+use manufacture_engine::ECS::fetch::{Fetch, FetchRes};
 use manufacture_engine::ECS::entity::EntityBuilder;
+use crate::comp::{Tag, TagId, Tags};
+use crate::resources::TagIndex;
 
+mod number;
 mod vector;
+mod matrix;
+mod easing;
+mod shape;
+mod prefab;
+mod scene;
+#[cfg(feature = "serde")]
+mod snapshot;
+pub use number::*;
 pub use vector::*;
+pub use matrix::*;
+pub use easing::*;
+pub use shape::*;
+pub use prefab::*;
+pub use scene::*;
+#[cfg(feature = "serde")]
+pub use snapshot::*;
 
 /// # Query Filter: With
 /// Only allows Entities that have the specified Component to pass through
@@ -40,11 +61,174 @@ impl<C: Component> QueryFilter for Without<C>{
     }
 }
 
+/// # Query Filter: With Tag
+/// Only allows Entities tagged with the specified Tag to pass through
+/// 
+/// Looks the tag up in `TagIndex` Resource, or in `Tags` if they changed since the index was updated
+pub struct WithTag<T: Tag>(PhantomData<T>);
+impl<T: Tag> QueryFilter for WithTag<T>{
+    type Item<'b> = (FetchRes<'b, TagIndex>, Fetch<'b, Tags>, TagId);
+
+    fn fetch<'a>(world: &'a World) -> Self::Item<'a> {
+        (world.fetch_res::<TagIndex>(), world.fetch::<Tags>(), TagId::of::<T>())
+    }
+
+    fn filter<'qref, 'query: 'qref>(fetched: &'qref Self::Item<'query>, index: &usize) -> bool {
+        has_tag(fetched, *index)
+    }
+}
+
+/// # Query Filter: Without Tag
+/// Only allows Entities not tagged with the specified Tag to pass through
+/// 
+/// Looks the tag up in `TagIndex` Resource, or in `Tags` if they changed since the index was updated
+pub struct WithoutTag<T: Tag>(PhantomData<T>);
+impl<T: Tag> QueryFilter for WithoutTag<T>{
+    type Item<'b> = (FetchRes<'b, TagIndex>, Fetch<'b, Tags>, TagId);
+
+    fn fetch<'a>(world: &'a World) -> Self::Item<'a> {
+        (world.fetch_res::<TagIndex>(), world.fetch::<Tags>(), TagId::of::<T>())
+    }
+
+    fn filter<'qref, 'query: 'qref>(fetched: &'qref Self::Item<'query>, index: &usize) -> bool {
+        !has_tag(fetched, *index)
+    }
+}
+
+/// Checks the tag in `Tags` changed directly, `TagIndex` is behind on those
+/// 
+/// Entities without `Tags` have no tags, even if the index still lists them
+fn has_tag(fetched: &(FetchRes<'_, TagIndex>, Fetch<'_, Tags>, TagId), id: usize) -> bool{
+    match fetched.1.get(&id){
+        Some(tags) if tags.is_dirty() => tags.contains(fetched.2),
+        Some(_) => fetched.0.has(id, fetched.2),
+        None => false,
+    }
+}
+
+/// `EntityPrefab::ID` of Prefabs that don't set their own
+pub const DEFAULT_PREFAB_ID: &str = "idkfa";
+
 /// # Entity Prefab trait
 /// A tiny rudimentary trait to make spawning Entities with Components easier
 /// 
 /// `spawn` method takes `&self` so that you can send custom data for Prefab to have right away
+/// 
+/// `ID` is the name the Prefab is registered under in `PrefabRegistry`.
+/// Prefabs that get registered have to override it with a unique one, `PrefabRegistry` refuses the default.
+/// The default is only fine for Prefabs spawned directly
 pub trait EntityPrefab{
-    const ID: &'static str = "idkfa";
+    const ID: &'static str = DEFAULT_PREFAB_ID;
     fn spawn(&self, builder: EntityBuilder<'_>);
+}
+
+/// Reasons a Command can fail, see `CommandFailed` Event
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError{
+    /// The Entity behind this Token has despawned
+    StaleToken(Token),
+    /// There's no Entity with this ID
+    StaleID(usize),
+    /// There's no Prefab with this ID in `PrefabRegistry`
+    MissingPrefab(String),
+    /// There's no Scene with this ID in `SceneRegistry`
+    MissingScene(String),
+    /// The Scene with this ID is already loaded
+    SceneAlreadyLoaded(String),
+    /// The Entity doesn't have the Component with this ID
+    MissingComponent(Token, &'static str),
+    /// Attaching the child would make it an ancestor of itself
+    HierarchyCycle{
+        parent: Token,
+        child: Token
+    },
+    /// The `SpawnHandle` is still empty, the spawning Command hasn't run yet
+    UnresolvedHandle,
+    /// Anything else, for Commands from outside the Core library
+    Custom(String),
+}
+impl std::fmt::Display for CommandError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            CommandError::StaleToken(token) => f.write_fmt(format_args!("Entity {} has despawned", token.id())),
+            CommandError::StaleID(id) => f.write_fmt(format_args!("There is no Entity {}", id)),
+            CommandError::MissingPrefab(id) => f.write_fmt(format_args!("Prefab `{}` is not registered", id)),
+            CommandError::MissingScene(id) => f.write_fmt(format_args!("Scene `{}` is not registered", id)),
+            CommandError::SceneAlreadyLoaded(id) => f.write_fmt(format_args!("Scene `{}` is already loaded", id)),
+            CommandError::MissingComponent(token, comp) => f.write_fmt(format_args!("Entity {} has no `{}`", token.id(), comp)),
+            CommandError::HierarchyCycle{parent, child} => f.write_fmt(format_args!("Entity {} cannot be a child of it's descendant {}", child.id(), parent.id())),
+            CommandError::UnresolvedHandle => f.write_str("The SpawnHandle is empty, it's Entity has not spawned yet"),
+            CommandError::Custom(message) => f.write_str(message),
+        }
+    }
+}
+impl std::error::Error for CommandError{}
+
+/// # Component Snapshot
+/// Holds copies of an Entity's Components, keyed by the Component's ID
+/// 
+/// Filled in by the `DespawnSnapshots` Resource right before an Entity gets despawned
+pub struct ComponentSnapshot{
+    inner: HashMap<&'static str, Box<dyn Any>>
+}
+impl ComponentSnapshot{
+    pub fn new() -> Self{
+        Self{
+            inner: HashMap::new(),
+        }
+    }
+    /// Get the copy of a given Component, if it was captured
+    pub fn get<C: Component>(&self) -> Option<&C>{
+        self.inner.get(C::ID)?.downcast_ref::<C>()
+    }
+    /// Check if a given Component was captured
+    pub fn has<C: Component>(&self) -> bool{
+        self.inner.contains_key(C::ID)
+    }
+    /// Take the copy of a given Component out of the Snapshot
+    pub fn take<C: Component>(&mut self) -> Option<C>{
+        self.inner.remove(C::ID)?.downcast::<C>().ok().map(|comp| *comp)
+    }
+    pub(crate) fn insert_boxed(&mut self, id: &'static str, comp: Box<dyn Any>){
+        self.inner.insert(id, comp);
+    }
+    pub fn is_empty(&self) -> bool{
+        self.inner.is_empty()
+    }
+}
+impl Default for ComponentSnapshot{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// # Spawn Handle
+/// Gets the Token of an Entity spawned by a Command once the Command runs
+/// 
+/// Clones share the same slot, so you can keep one and hand the other to the Command, even if it's on another thread
+/// 
+/// Make one before sending the spawning Command to refer to the Entity in further Commands with `AfterSpawn`.
+/// It doesn't reserve a Token: the Entity gets it's Token only once the spawning Command runs,
+/// until then the handle stays empty. See `SpawnPrefabWith::fill_handle`
+#[derive(Clone, Default)]
+pub struct SpawnHandle{
+    inner: Arc<Mutex<Option<Token>>>
+}
+impl SpawnHandle{
+    pub fn new() -> Self{
+        Self{
+            inner: Arc::new(Mutex::new(None)),
+        }
+    }
+    /// Get the spawned Entity's Token, if the Command has run already
+    pub fn get(&self) -> Option<Token>{
+        *self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    pub(crate) fn set(&self, token: Token){
+        *self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(token);
+    }
+    /// Check if anything but this handle points to the same slot
+    pub(crate) fn is_shared(&self) -> bool{
+        Arc::strong_count(&self.inner) > 1
+    }
 }
\ No newline at end of file
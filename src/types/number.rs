@@ -0,0 +1,178 @@
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::{
+    Add,
+    AddAssign,
+    Sub,
+    SubAssign,
+    Mul,
+    MulAssign,
+    Div,
+    DivAssign,
+    Neg
+};
+
+/// # Number trait
+/// Anything that Vectors can be made of
+///
+/// Implemented for all primitive integer and float types
+pub trait Number:
+    Copy + PartialEq + PartialOrd + Default + Debug + Display
+    + Add<Output = Self> + AddAssign
+    + Sub<Output = Self> + SubAssign
+    + Mul<Output = Self> + MulAssign
+    + Div<Output = Self> + DivAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+    /// Convert to `f32`, losing precision if needed
+    fn to_f32(self) -> f32;
+    /// Convert from `f32`, rounding towards zero and clamping to the type's bounds
+    fn from_f32(value: f32) -> Self;
+    /// Wrap the value so it can be converted to another Number
+    fn to_primitive(self) -> Primitive;
+    /// Convert from any primitive the same way `as` does
+    fn from_primitive(value: Primitive) -> Self;
+    /// Convert from any other Number the same way `as` does
+    ///
+    /// Floats get rounded towards zero and clamped to the type's bounds,
+    /// integers that don't fit get wrapped, and converting to the same type returns the value untouched
+    fn from_number<U: Number>(value: U) -> Self{
+        Self::from_primitive(value.to_primitive())
+    }
+}
+
+/// A value of any primitive Number type
+///
+/// Lets `Number::from_number` convert between any two types without going through a lossy one in between
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub enum Primitive{
+    I8(i8), I16(i16), I32(i32), I64(i64), Isize(isize),
+    U8(u8), U16(u16), U32(u32), U64(u64), Usize(usize),
+    F32(f32), F64(f64)
+}
+
+/// # Signed trait
+/// Numbers that can go negative
+pub trait Signed: Number + Neg<Output = Self>{
+    fn abs(self) -> Self{
+        if self < Self::ZERO {-self} else {self}
+    }
+}
+
+/// # Float trait
+/// Numbers with a fractional part, needed for lengths and angles
+pub trait Float: Signed{
+    fn sqrt(self) -> Self;
+    /// Returns `sqrt(self² + other²)` without overflowing in between
+    fn hypot(self, other: Self) -> Self;
+    fn acos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    /// Returns sine and cosine at once
+    fn sin_cos(self) -> (Self, Self);
+    /// Returns `false` for NaN and infinities
+    fn is_finite(self) -> bool;
+}
+
+/// # Integer trait
+/// Whole numbers, used for grid coordinates
+pub trait Integer: Number + Eq + Ord + Hash{
+    /// Unsigned counterpart of the type, big enough to hold any distance between two values
+    type Unsigned: Integer;
+    /// Returns the absolute difference between `self` and `other`
+    fn abs_diff(self, other: Self) -> Self::Unsigned;
+    /// Returns `self + other`, stopping at the type's bounds instead of overflowing
+    fn saturating_add(self, other: Self) -> Self;
+}
+
+macro_rules! number {
+    ($($ty:ident($variant:ident): $zero:literal, $one:literal);* $(;)?) => {$(
+        impl Number for $ty{
+            const ZERO: Self = $zero;
+            const ONE: Self = $one;
+
+            fn to_f32(self) -> f32 {
+                self as f32
+            }
+            fn from_f32(value: f32) -> Self {
+                value as $ty
+            }
+            fn to_primitive(self) -> Primitive {
+                Primitive::$variant(self)
+            }
+            fn from_primitive(value: Primitive) -> Self {
+                match value{
+                    Primitive::I8(value) => value as $ty,
+                    Primitive::I16(value) => value as $ty,
+                    Primitive::I32(value) => value as $ty,
+                    Primitive::I64(value) => value as $ty,
+                    Primitive::Isize(value) => value as $ty,
+                    Primitive::U8(value) => value as $ty,
+                    Primitive::U16(value) => value as $ty,
+                    Primitive::U32(value) => value as $ty,
+                    Primitive::U64(value) => value as $ty,
+                    Primitive::Usize(value) => value as $ty,
+                    Primitive::F32(value) => value as $ty,
+                    Primitive::F64(value) => value as $ty,
+                }
+            }
+        }
+    )*};
+}
+number!(
+    i8(I8): 0, 1; i16(I16): 0, 1; i32(I32): 0, 1; i64(I64): 0, 1; isize(Isize): 0, 1;
+    u8(U8): 0, 1; u16(U16): 0, 1; u32(U32): 0, 1; u64(U64): 0, 1; usize(Usize): 0, 1;
+    f32(F32): 0.0, 1.0; f64(F64): 0.0, 1.0;
+);
+
+macro_rules! signed {
+    ($($ty:ty),*) => {$(
+        impl Signed for $ty{}
+    )*};
+}
+signed!(i8, i16, i32, i64, isize, f32, f64);
+
+macro_rules! float {
+    ($($ty:ty),*) => {$(
+        impl Float for $ty{
+            fn sqrt(self) -> Self {
+                <$ty>::sqrt(self)
+            }
+            fn hypot(self, other: Self) -> Self {
+                <$ty>::hypot(self, other)
+            }
+            fn acos(self) -> Self {
+                <$ty>::acos(self)
+            }
+            fn atan2(self, other: Self) -> Self {
+                <$ty>::atan2(self, other)
+            }
+            fn sin_cos(self) -> (Self, Self) {
+                <$ty>::sin_cos(self)
+            }
+            fn is_finite(self) -> bool {
+                <$ty>::is_finite(self)
+            }
+        }
+    )*};
+}
+float!(f32, f64);
+
+macro_rules! integer {
+    ($($ty:ty => $unsigned:ty),*) => {$(
+        impl Integer for $ty{
+            type Unsigned = $unsigned;
+
+            fn abs_diff(self, other: Self) -> Self::Unsigned {
+                <$ty>::abs_diff(self, other)
+            }
+            fn saturating_add(self, other: Self) -> Self {
+                <$ty>::saturating_add(self, other)
+            }
+        }
+    )*};
+}
+integer!(
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize,
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, usize => usize
+);
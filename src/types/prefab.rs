@@ -0,0 +1,660 @@
+use super::*;
+use crate::comp::*;
+
+/// Errors that can occur when working with Prefabs
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrefabError{
+    /// A Prefab with this ID is already registered
+    DuplicateID(String),
+    /// A Prefab type kept the default `EntityPrefab::ID`, so it can't be told apart from other ones
+    DefaultID(String),
+    /// A Prefab file could not be read
+    Io{
+        file: String,
+        message: String
+    },
+    /// A Prefab file has a mistake in it
+    Parse{
+        file: String,
+        line: usize,
+        message: String
+    },
+}
+impl std::fmt::Display for PrefabError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            PrefabError::DuplicateID(id) => f.write_fmt(format_args!("Prefab `{}` is already registered", id)),
+            PrefabError::DefaultID(prefab) => f.write_fmt(format_args!("Prefab `{}` has to override the default ID to be registered", prefab)),
+            PrefabError::Io{file, message} => f.write_fmt(format_args!("{}: {}", file, message)),
+            PrefabError::Parse{file, line, message} => f.write_fmt(format_args!("{}:{}: {}", file, line, message)),
+        }
+    }
+}
+impl std::error::Error for PrefabError{}
+
+/// # Prefab Component trait
+/// Lets a Component be built from a Prefab file
+///
+/// Register it in `PrefabLoader` Resource to make it usable in Prefab files.
+/// Loaded Prefabs keep a copy of the Component around, so it has to be `Send` and `Sync`
+pub trait PrefabComponent: Component + Clone + Send + Sync{
+    fn from_fields(fields: &PrefabFields) -> Result<Self, PrefabError>;
+    /// What `+=` puts between an inherited list field and the appended items
+    /// 
+    /// Override it for fields that aren't `, ` separated
+    fn list_separator(_field: &str) -> &'static str{
+        ", "
+    }
+}
+
+/// Picks the list separator of a Component's field, see `PrefabComponent::list_separator`
+pub type ListSeparator<'a> = &'a dyn Fn(&str, &str) -> &'static str;
+
+/// # Prefab Value trait
+/// A type that can be read from a single Prefab field value
+pub trait FromPrefabValue: Sized{
+    fn from_value(value: &str) -> Result<Self, String>;
+}
+
+/// How a field in a Prefab file is applied on top of the Prefab it extends
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldOp{
+    /// `key = value`, replaces the value
+    Set,
+    /// `key += value`, adds numbers together or appends to a list
+    Add,
+    /// `key *= value`, multiplies numbers
+    Mul,
+}
+
+#[derive(Clone)]
+struct Field{
+    key: String,
+    value: String,
+    op: FieldOp,
+    file: String,
+    line: usize
+}
+
+/// # Prefab Fields
+/// The fields of a single Component section in a Prefab file
+///
+/// All errors it returns point at the line the field is on
+#[derive(Clone)]
+pub struct PrefabFields{
+    file: String,
+    line: usize,
+    comp: String,
+    fields: Vec<Field>
+}
+impl PrefabFields{
+    pub fn new(file: &str, line: usize, comp: &str) -> Self{
+        Self{
+            file: file.to_string(),
+            line,
+            comp: comp.to_string(),
+            fields: Vec::new(),
+        }
+    }
+    /// ID of the Component these fields are for
+    pub fn comp(&self) -> &str{
+        &self.comp
+    }
+    /// File these fields come from
+    pub fn file(&self) -> &str{
+        &self.file
+    }
+    /// Line of the Component section header
+    pub fn line(&self) -> usize{
+        self.line
+    }
+    /// Get the unparsed value of a field
+    pub fn raw(&self, key: &str) -> Option<&str>{
+        self.field(key).map(|field| field.value.as_str())
+    }
+    /// Set a field's value, replacing the old one if there was one
+    pub fn set(&mut self, key: &str, value: &str, line: usize){
+        let file = self.file.clone();
+        self.set_op(key, value, FieldOp::Set, &file, line);
+    }
+    /// Iterate over `(key, value)` of all fields
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)>{
+        self.fields.iter().map(|field| (field.key.as_str(), field.value.as_str()))
+    }
+    /// Parse a field if it's present
+    pub fn get<T: FromPrefabValue>(&self, key: &str) -> Result<Option<T>, PrefabError>{
+        match self.field(key){
+            Some(field) => T::from_value(&field.value)
+                .map(Some)
+                .map_err(|message| field_error(field, format!("{}.{}: {}", self.comp, key, message))),
+            None => Ok(None),
+        }
+    }
+    /// Parse a field, falling back to `default` if it's not present
+    pub fn get_or<T: FromPrefabValue>(&self, key: &str, default: T) -> Result<T, PrefabError>{
+        Ok(self.get(key)?.unwrap_or(default))
+    }
+    /// Parse a field that has to be present
+    pub fn require<T: FromPrefabValue>(&self, key: &str) -> Result<T, PrefabError>{
+        match self.get(key)?{
+            Some(value) => Ok(value),
+            None => Err(self.error(format!("{} is missing field `{}`", self.comp, key))),
+        }
+    }
+    /// Fail on any field that's not in `known`
+    ///
+    /// Catches typos in field names that would otherwise be silently ignored
+    pub fn expect_only(&self, known: &[&str]) -> Result<(), PrefabError>{
+        match self.fields.iter().find(|field| !known.contains(&field.key.as_str())){
+            Some(field) => Err(field_error(field, format!("{} has no field `{}`", self.comp, field.key))),
+            None => Ok(()),
+        }
+    }
+    /// Make an error pointing at the Component section header
+    pub fn error(&self, message: impl Into<String>) -> PrefabError{
+        PrefabError::Parse{
+            file: self.file.clone(),
+            line: self.line,
+            message: message.into(),
+        }
+    }
+
+    fn field(&self, key: &str) -> Option<&Field>{
+        self.fields.iter().find(|field| field.key == key)
+    }
+    fn set_op(&mut self, key: &str, value: &str, op: FieldOp, file: &str, line: usize){
+        let field = Field{
+            key: key.to_string(),
+            value: value.to_string(),
+            op,
+            file: file.to_string(),
+            line,
+        };
+        match self.fields.iter_mut().find(|old| old.key == key){
+            Some(old) => *old = field,
+            None => self.fields.push(field),
+        }
+    }
+    /// Apply fields of a derived Prefab on top of these
+    fn apply(&mut self, other: &PrefabFields, separator: ListSeparator<'_>) -> Result<(), PrefabError>{
+        for field in other.fields.iter(){
+            let value = match (field.op, self.field(&field.key)){
+                (FieldOp::Set, _) => field.value.clone(),
+                (op, Some(base)) => apply_op(&base.value, op, &field.value, separator(&self.comp, &field.key))
+                    .map_err(|message| field_error(field, format!("{}.{}: {}", self.comp, field.key, message)))?,
+                (_, None) => return Err(field_error(field, format!("{}.{} has no inherited value to modify", self.comp, field.key))),
+            };
+            self.set_op(&field.key, &value, FieldOp::Set, &field.file, field.line);
+        }
+        Ok(())
+    }
+    /// Fail if any field still needs a base value
+    fn expect_resolved(&self) -> Result<(), PrefabError>{
+        match self.fields.iter().find(|field| field.op != FieldOp::Set){
+            Some(field) => Err(field_error(field, format!("{}.{} modifies a value, but the Prefab doesn't extend another one", self.comp, field.key))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn field_error(field: &Field, message: String) -> PrefabError{
+    PrefabError::Parse{
+        file: field.file.clone(),
+        line: field.line,
+        message,
+    }
+}
+
+/// Applies `+=` or `*=` to a raw field value
+///
+/// Numbers and number lists of the same length are added or multiplied element-wise,
+/// a single number multiplies every element of a list.
+/// Anything else can only be added, which appends it to the list after `separator`
+fn apply_op(base: &str, op: FieldOp, value: &str, separator: &str) -> Result<String, String>{
+    let numbers = |text: &str| split_list(text).map(|item| item.parse::<f64>()).collect::<Result<Vec<f64>, _>>().ok();
+
+    match (numbers(base), numbers(value), op){
+        (Some(base), Some(value), FieldOp::Add) if base.len() == value.len() => {
+            Ok(join_numbers(base.iter().zip(value.iter()).map(|(a, b)| a + b)))
+        }
+        (Some(base), Some(value), FieldOp::Mul) if base.len() == value.len() => {
+            Ok(join_numbers(base.iter().zip(value.iter()).map(|(a, b)| a * b)))
+        }
+        (Some(base), Some(value), FieldOp::Mul) if value.len() == 1 => {
+            Ok(join_numbers(base.iter().map(|a| a * value[0])))
+        }
+        (_, _, FieldOp::Mul) => Err(format!("Cannot multiply `{}` by `{}`", base, value)),
+        _ if base.trim().is_empty() => Ok(value.to_string()),
+        _ => Ok(format!("{}{}{}", base, separator, value)),
+    }
+}
+
+fn join_numbers(numbers: impl Iterator<Item = f64>) -> String{
+    numbers.map(|number| number.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// # Prefab Definition
+/// A single Prefab as written in a Prefab file, before it's turned into spawning instructions
+///
+/// A Prefab can `extends` another one, inheriting all of it's Components, see `resolve_prefabs`
+#[derive(Clone)]
+pub struct PrefabDef{
+    pub id: String,
+    pub file: String,
+    pub line: usize,
+    /// ID of the Prefab this one is built on top of, with the line it's set on
+    pub extends: Option<(String, usize)>,
+    /// IDs of inherited Components this Prefab drops
+    pub remove: Vec<String>,
+    pub components: Vec<PrefabFields>
+}
+
+/// Parse Prefab definitions out of text
+///
+/// The format is a small subset of TOML. Each Component gets a `[prefab.Component]` section with it's fields below:
+/// ```text
+/// # Comments start with `#`
+/// [orc.Transform2D]
+/// loc = 4.0, 2.0
+/// scale = 1.0, 1.0
+///
+/// [orc.Tags]
+/// tags = Enemy, Orc
+///
+/// [orc.CMDSprite]
+/// size_x = 1
+/// size_y = 1
+/// data = 'O' 00FF00 000000
+/// ```
+/// A bare `[prefab]` section holds settings of the Prefab itself, it may also be empty:
+/// ```text
+/// [elite_orc]
+/// extends = orc
+/// remove = PlayerController
+///
+/// [elite_orc.Transform2D]
+/// scale *= 1.5
+///
+/// [elite_orc.Tags]
+/// tags += Elite
+/// ```
+/// `+=` and `*=` modify the value inherited from the base Prefab, `=` replaces it
+///
+/// `file` is only used for error messages
+pub fn parse_prefabs(file: &str, source: &str) -> Result<Vec<PrefabDef>, PrefabError>{
+    let mut defs: Vec<PrefabDef> = Vec::new();
+    // Index of the Prefab and it's Component we're currently filling in
+    let mut current: Option<(usize, Option<usize>)> = None;
+
+    for (index, raw_line) in source.lines().enumerate(){
+        let line = index + 1;
+        let text = raw_line.trim();
+        let error = |message: String| PrefabError::Parse{file: file.to_string(), line, message};
+
+        if text.is_empty() || text.starts_with('#'){
+            continue
+        }
+
+        if let Some(header) = text.strip_prefix('['){
+            let header = header.strip_suffix(']').ok_or_else(|| error("Section header is missing `]`".to_string()))?.trim();
+            let (id, comp) = match header.split_once('.'){
+                Some((id, comp)) => (id.trim(), Some(comp.trim())),
+                None => (header, None),
+            };
+            if !is_identifier(id){
+                return Err(error(format!("`{}` is not a valid Prefab ID", id)))
+            }
+
+            let def_index = match defs.iter().position(|def| def.id == id){
+                Some(def_index) => def_index,
+                None => {
+                    defs.push(PrefabDef{
+                        id: id.to_string(),
+                        file: file.to_string(),
+                        line,
+                        extends: None,
+                        remove: Vec::new(),
+                        components: Vec::new(),
+                    });
+                    defs.len() - 1
+                }
+            };
+
+            let comp_index = match comp{
+                Some(comp) => {
+                    if !is_identifier(comp){
+                        return Err(error(format!("`{}` is not a valid Component ID", comp)))
+                    }
+                    let def = &mut defs[def_index];
+                    if def.components.iter().any(|fields| fields.comp() == comp){
+                        return Err(error(format!("Prefab `{}` already has a `{}` section", id, comp)))
+                    }
+                    def.components.push(PrefabFields::new(file, line, comp));
+                    Some(def.components.len() - 1)
+                }
+                None => None,
+            };
+            current = Some((def_index, comp_index));
+            continue
+        }
+
+        let (key, value) = text.split_once('=').ok_or_else(|| error(format!("Expected `key = value`, found `{}`", text)))?;
+        let (key, op) = match key.trim_end(){
+            key if key.ends_with('+') => (key[..key.len() - 1].trim(), FieldOp::Add),
+            key if key.ends_with('*') => (key[..key.len() - 1].trim(), FieldOp::Mul),
+            key => (key.trim(), FieldOp::Set),
+        };
+        let value = unquote(value.trim());
+        if !is_identifier(key){
+            return Err(error(format!("`{}` is not a valid field name", key)))
+        }
+
+        match current{
+            Some((def_index, Some(comp_index))) => {
+                let fields = &mut defs[def_index].components[comp_index];
+                if fields.raw(key).is_some(){
+                    return Err(error(format!("Field `{}` is set twice", key)))
+                }
+                fields.set_op(key, value, op, file, line);
+            }
+            Some((def_index, None)) => {
+                let def = &mut defs[def_index];
+                match (key, op){
+                    ("extends", FieldOp::Set) => {
+                        if !is_identifier(value){
+                            return Err(error(format!("`{}` is not a valid Prefab ID", value)))
+                        }
+                        def.extends = Some((value.to_string(), line));
+                    }
+                    ("remove", FieldOp::Set) => def.remove.extend(split_list(value).map(|comp| comp.to_string())),
+                    _ => return Err(error(format!("Unknown Prefab setting `{}` in `{}`", key, def.id))),
+                }
+            }
+            None => return Err(error("Field outside of any section".to_string())),
+        }
+    }
+
+    Ok(defs)
+}
+
+/// Resolve inheritance of Prefab definitions
+///
+/// Every returned definition has all of it's inherited Components merged in and no longer `extends` anything.
+/// Base Prefabs are looked up in `defs` first, then in `known` for Prefabs that were resolved before
+///
+/// `separator` picks what `+=` appends lists with for a given Component and field,
+/// `PrefabLoader` uses `PrefabComponent::list_separator` of the registered Components
+/// 
+/// Fails on unknown base Prefabs, on cycles, like `a extends b, b extends a`,
+/// and with `PrefabError::DuplicateID` if two definitions in `defs` share an ID
+pub fn resolve_prefabs(defs: &[PrefabDef], known: &HashMap<String, PrefabDef>, separator: ListSeparator<'_>) -> Result<Vec<PrefabDef>, PrefabError>{
+    for (index, def) in defs.iter().enumerate(){
+        if defs[..index].iter().any(|other| other.id == def.id){
+            return Err(PrefabError::DuplicateID(def.id.clone()))
+        }
+    }
+
+    let mut resolved: HashMap<String, PrefabDef> = HashMap::new();
+    let mut out = Vec::with_capacity(defs.len());
+    for def in defs.iter(){
+        out.push(resolve_def(def, defs, known, separator, &mut resolved, &mut Vec::new())?);
+    }
+    Ok(out)
+}
+
+fn resolve_def(
+    def: &PrefabDef,
+    defs: &[PrefabDef],
+    known: &HashMap<String, PrefabDef>,
+    separator: ListSeparator<'_>,
+    resolved: &mut HashMap<String, PrefabDef>,
+    chain: &mut Vec<String>
+) -> Result<PrefabDef, PrefabError>{
+    if let Some(done) = resolved.get(&def.id){
+        return Ok(done.clone())
+    }
+
+    let (base_id, line) = match &def.extends{
+        Some(extends) => extends,
+        None => {
+            for fields in def.components.iter(){
+                fields.expect_resolved()?;
+            }
+            if let Some(comp) = def.remove.first(){
+                return Err(PrefabError::Parse{
+                    file: def.file.clone(),
+                    line: def.line,
+                    message: format!("Prefab `{}` removes `{}`, but doesn't extend another Prefab", def.id, comp),
+                })
+            }
+            resolved.insert(def.id.clone(), def.clone());
+            return Ok(def.clone())
+        }
+    };
+    let error = |message: String| PrefabError::Parse{file: def.file.clone(), line: *line, message};
+
+    chain.push(def.id.clone());
+    if chain.contains(base_id){
+        chain.push(base_id.clone());
+        return Err(error(format!("Prefab inheritance cycle: {}", chain.join(" -> "))))
+    }
+
+    let base = match (defs.iter().find(|other| other.id == *base_id), known.get(base_id)){
+        (Some(base), _) => resolve_def(base, defs, known, separator, resolved, chain)?,
+        (None, Some(base)) => base.clone(),
+        (None, None) => return Err(error(format!("Prefab `{}` extends unknown Prefab `{}`", def.id, base_id))),
+    };
+    chain.pop();
+
+    let mut components = base.components;
+    for comp in def.remove.iter(){
+        match components.iter().position(|fields| fields.comp() == comp){
+            Some(index) => {
+                components.remove(index);
+            }
+            None => return Err(error(format!("Prefab `{}` removes `{}`, but `{}` doesn't have it", def.id, comp, base_id))),
+        }
+    }
+    for fields in def.components.iter(){
+        match components.iter_mut().find(|base_fields| base_fields.comp() == fields.comp()){
+            Some(base_fields) => base_fields.apply(fields, separator)?,
+            None => {
+                fields.expect_resolved()?;
+                components.push(fields.clone());
+            }
+        }
+    }
+
+    let done = PrefabDef{
+        id: def.id.clone(),
+        file: def.file.clone(),
+        line: def.line,
+        extends: None,
+        remove: Vec::new(),
+        components,
+    };
+    resolved.insert(def.id.clone(), done.clone());
+    Ok(done)
+}
+
+fn is_identifier(text: &str) -> bool{
+    !text.is_empty() && text.chars().all(|ch| ch.is_alphanumeric() || ch == '_')
+}
+
+/// Strips surrounding double quotes, if there are any
+fn unquote(value: &str) -> &str{
+    value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).unwrap_or(value)
+}
+
+/// Splits a comma-separated list into trimmed items
+fn split_list(value: &str) -> impl Iterator<Item = &str>{
+    value.split(',').map(|item| item.trim()).filter(|item| !item.is_empty())
+}
+
+macro_rules! from_prefab_value_parse {
+    ($($ty:ty),*) => {$(
+        impl FromPrefabValue for $ty{
+            fn from_value(value: &str) -> Result<Self, String> {
+                value.parse().map_err(|_| format!("`{}` is not a valid {}", value, stringify!($ty)))
+            }
+        }
+    )*};
+}
+from_prefab_value_parse!(f32, f64, u8, u16, u32, u64, usize, i8, i16, i32, i64, bool);
+
+impl FromPrefabValue for String{
+    fn from_value(value: &str) -> Result<Self, String> {
+        Ok(value.to_string())
+    }
+}
+impl FromPrefabValue for char{
+    fn from_value(value: &str) -> Result<Self, String> {
+        let value = value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')).unwrap_or(value);
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()){
+            (Some(ch), None) => Ok(ch),
+            _ => Err(format!("`{}` is not a single character", value)),
+        }
+    }
+}
+impl<T: FromPrefabValue> FromPrefabValue for Vec<T>{
+    fn from_value(value: &str) -> Result<Self, String> {
+        split_list(value).map(T::from_value).collect()
+    }
+}
+impl FromPrefabValue for Vector2{
+    fn from_value(value: &str) -> Result<Self, String> {
+        match Vec::<f32>::from_value(value)?.as_slice(){
+            [x, y] => Ok(Vector2::new(*x, *y)),
+            _ => Err(format!("`{}` is not a 2D Vector, expected `x, y`", value)),
+        }
+    }
+}
+impl FromPrefabValue for Vector3{
+    fn from_value(value: &str) -> Result<Self, String> {
+        match Vec::<f32>::from_value(value)?.as_slice(){
+            [x, y, z] => Ok(Vector3::new(*x, *y, *z)),
+            _ => Err(format!("`{}` is not a 3D Vector, expected `x, y, z`", value)),
+        }
+    }
+}
+
+// -- Core Components --
+
+impl PrefabComponent for Transform2D{
+    fn from_fields(fields: &PrefabFields) -> Result<Self, PrefabError> {
+        fields.expect_only(&["loc", "rot", "scale"])?;
+        Ok(Self{
+            loc: fields.get_or("loc", Vector2::new(0.0, 0.0))?,
+            rot: fields.get_or("rot", 0.0)?,
+            scale: fields.get_or("scale", Vector2::new(1.0, 1.0))?,
+        })
+    }
+}
+impl PrefabComponent for Transform3D{
+    fn from_fields(fields: &PrefabFields) -> Result<Self, PrefabError> {
+        fields.expect_only(&["loc", "rot", "scale"])?;
+        Ok(Self{
+            loc: fields.get_or("loc", Vector3::new(0.0, 0.0, 0.0))?,
+            rot: fields.get_or("rot", Vector3::new(0.0, 0.0, 0.0))?,
+            scale: fields.get_or("scale", Vector3::new(1.0, 1.0, 1.0))?,
+        })
+    }
+}
+impl PrefabComponent for Tags{
+    fn from_fields(fields: &PrefabFields) -> Result<Self, PrefabError> {
+        fields.expect_only(&["tags"])?;
+        let mut tags = Tags::new();
+        for tag in fields.get_or::<Vec<String>>("tags", Vec::new())?{
+            tags.tag_id(&tag);
+        }
+        Ok(tags)
+    }
+}
+impl PrefabComponent for PlayerController{
+    fn from_fields(fields: &PrefabFields) -> Result<Self, PrefabError> {
+        fields.expect_only(&["pid", "active"])?;
+        Ok(Self{
+            pid: fields.get_or("pid", 0)?,
+            active: fields.get_or("active", true)?,
+        })
+    }
+}
+impl PrefabComponent for Persistent{
+    fn from_fields(fields: &PrefabFields) -> Result<Self, PrefabError> {
+        fields.expect_only(&[])?;
+        Ok(Self)
+    }
+}
+impl PrefabComponent for CMDSprite{
+    /// Pixels in `data` are separated with `;`, each one being `'ch' RRGGBB RRGGBB`:
+    /// ```text
+    /// data = '/' FFFFFF 000000; '\' FFFFFF 000000
+    /// ```
+    fn from_fields(fields: &PrefabFields) -> Result<Self, PrefabError> {
+        fields.expect_only(&["size_x", "size_y", "z_index", "data"])?;
+        let size_x: u8 = fields.require("size_x")?;
+        let size_y: u8 = fields.require("size_y")?;
+        let data = match fields.raw("data"){
+            Some(raw) => parse_sprite_data(raw).map_err(|message| fields.error(format!("CMDSprite.data: {}", message)))?,
+            None => vec![(' ', (255, 255, 255), (0, 0, 0)); size_x as usize * size_y as usize],
+        };
+        if data.len() != size_x as usize * size_y as usize{
+            return Err(fields.error(format!("CMDSprite has {} pixels, but it's size is {}x{}", data.len(), size_x, size_y)))
+        }
+        Ok(Self{
+            size_x,
+            size_y,
+            z_index: fields.get_or("z_index", 0)?,
+            data,
+        })
+    }
+    /// Pixels are `;` separated, so `data +=` appends them with `; `
+    fn list_separator(field: &str) -> &'static str{
+        match field{
+            "data" => "; ",
+            _ => ", ",
+        }
+    }
+}
+
+fn parse_sprite_data(raw: &str) -> Result<Vec<CMDPixel>, String>{
+    let mut pixels = Vec::new();
+    let mut rest = raw.trim();
+
+    while !rest.is_empty(){
+        // The character is quoted so that `;`, spaces and the like can be used too
+        let mut chars = rest.chars();
+        let ch = match (chars.next(), chars.next(), chars.next()){
+            (Some('\''), Some(ch), Some('\'')) => ch,
+            _ => return Err(format!("Expected a quoted character at `{}`", rest)),
+        };
+        rest = chars.as_str();
+
+        let (pixel, tail) = match rest.split_once(';'){
+            Some((pixel, tail)) => (pixel, tail),
+            None => (rest, ""),
+        };
+        let mut colors = pixel.split_whitespace();
+        let (fg, bg) = match (colors.next(), colors.next(), colors.next()){
+            (Some(fg), Some(bg), None) => (parse_color(fg)?, parse_color(bg)?),
+            _ => return Err(format!("Expected foreground and background colors after '{}'", ch)),
+        };
+
+        pixels.push((ch, fg, bg));
+        rest = tail.trim();
+    }
+
+    Ok(pixels)
+}
+
+fn parse_color(hex: &str) -> Result<(u8, u8, u8), String>{
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    let channel = |range: std::ops::Range<usize>| {
+        hex.get(range).and_then(|part| u8::from_str_radix(part, 16).ok())
+    };
+    match (hex.len(), channel(0..2), channel(2..4), channel(4..6)){
+        (6, Some(r), Some(g), Some(b)) => Ok((r, g, b)),
+        _ => Err(format!("`{}` is not a RRGGBB color", hex)),
+    }
+}
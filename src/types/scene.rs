@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use super::*;
+use crate::resources::PrefabSpawner;
+
+/// # Scene
+/// A named set of Entities that get spawned and despawned together
+///
+/// Build one in code out of Prefabs, or load it from a file with `PrefabLoader::load_scene_file`,
+/// then register it in `SceneRegistry` Resource and send `LoadScene` or `SwitchScene` Commands:
+/// ```ignore
+/// let scene = Scene::new("level_1")
+///     .with(Orc{loc: Vector2::new(4.0, 2.0)})
+///     .with(Orc{loc: Vector2::new(8.0, 2.0)});
+/// world.fetch_res_mut::<SceneRegistry>().register(scene);
+/// ```
+#[derive(Clone)]
+pub struct Scene{
+    id: String,
+    entities: Vec<PrefabSpawner>
+}
+impl Scene{
+    /// Create an empty Scene
+    pub fn new(id: impl Into<String>) -> Self{
+        Self{
+            id: id.into(),
+            entities: Vec::new(),
+        }
+    }
+    /// Add an Entity built from a Prefab
+    pub fn with<T: EntityPrefab + Send + Sync + 'static>(mut self, prefab: T) -> Self{
+        self.add(prefab);
+        self
+    }
+    /// Add an Entity built from raw spawning instructions
+    pub fn with_spawner(mut self, spawner: PrefabSpawner) -> Self{
+        self.add_spawner(spawner);
+        self
+    }
+    /// Add an Entity built from a Prefab
+    pub fn add<T: EntityPrefab + Send + Sync + 'static>(&mut self, prefab: T){
+        self.add_spawner(Arc::new(move |builder| prefab.spawn(builder)));
+    }
+    /// Add an Entity built from raw spawning instructions
+    pub fn add_spawner(&mut self, spawner: PrefabSpawner){
+        self.entities.push(spawner);
+    }
+    pub fn id(&self) -> &str{
+        &self.id
+    }
+    /// Number of Entities the Scene spawns
+    pub fn len(&self) -> usize{
+        self.entities.len()
+    }
+    pub fn is_empty(&self) -> bool{
+        self.entities.is_empty()
+    }
+    /// Iterate over spawning instructions of the Scene's Entities
+    pub fn iter(&self) -> std::slice::Iter<'_, PrefabSpawner>{
+        self.entities.iter()
+    }
+}
@@ -0,0 +1,551 @@
+use super::{Vector2, Vector3};
+
+/// Screen-space name for `Aabb2`
+pub type Rect = Aabb2;
+
+/// Default name for `Segment2`, as most Segments are on the screen
+pub type Segment = Segment2;
+
+/// # 2D Axis-Aligned Bounding Box
+/// A rectangle that can't rotate, defined by it's lowest and highest corner
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Aabb2{
+    pub min: Vector2,
+    pub max: Vector2
+}
+impl Aabb2{
+    /// Create a box spanning between two corners, in any order
+    pub fn new(a: Vector2, b: Vector2) -> Self{
+        Self{
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+    /// Create a box of `size` centered on `center`
+    pub fn from_center(center: Vector2, size: Vector2) -> Self{
+        let half = size.abs() / 2.0;
+        Self{
+            min: center - half,
+            max: center + half,
+        }
+    }
+    /// Create the smallest box holding all the `points`, `None` if there are none
+    pub fn from_points(points: impl IntoIterator<Item = Vector2>) -> Option<Self>{
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Self::new(first, first), |out, point| out.expand_to(point)))
+    }
+    pub fn center(&self) -> Vector2{
+        (self.min + self.max) / 2.0
+    }
+    pub fn size(&self) -> Vector2{
+        self.max - self.min
+    }
+    pub fn area(&self) -> f32{
+        let size = self.size();
+        size.x * size.y
+    }
+    /// Check if `point` is inside the box, edges included
+    pub fn contains_point(&self, point: Vector2) -> bool{
+        self.min.x <= point.x && point.x <= self.max.x
+        && self.min.y <= point.y && point.y <= self.max.y
+    }
+    /// Check if `other` is entirely inside the box
+    pub fn contains(&self, other: &Self) -> bool{
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+    /// Check if the boxes overlap, touching edges count
+    pub fn intersects(&self, other: &Self) -> bool{
+        self.min.x <= other.max.x && other.min.x <= self.max.x
+        && self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+    /// Returns the overlapping area, or `None` if the boxes don't overlap
+    pub fn intersection(&self, other: &Self) -> Option<Self>{
+        if !self.intersects(other){
+            return None
+        }
+        Some(Self{
+            min: self.min.max(other.min),
+            max: self.max.min(other.max),
+        })
+    }
+    /// Returns the smallest box holding both boxes
+    pub fn union(&self, other: &Self) -> Self{
+        Self{
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+    /// Grow the box by `amount` on every side, negative `amount` shrinks it down to it's center
+    pub fn expand(&self, amount: f32) -> Self{
+        let center = self.center();
+        Self{
+            min: (self.min - Vector2::new(amount, amount)).min(center),
+            max: (self.max + Vector2::new(amount, amount)).max(center),
+        }
+    }
+    /// Grow the box just enough to hold `point`
+    pub fn expand_to(&self, point: Vector2) -> Self{
+        Self{
+            min: self.min.min(point),
+            max: self.max.max(point),
+        }
+    }
+    /// Returns the point in the box closest to `point`, `point` itself if it's inside
+    pub fn closest_point(&self, point: Vector2) -> Vector2{
+        point.clamp(self.min, self.max)
+    }
+}
+
+/// # 3D Axis-Aligned Bounding Box
+/// A box that can't rotate, defined by it's lowest and highest corner
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Aabb3{
+    pub min: Vector3,
+    pub max: Vector3
+}
+impl Aabb3{
+    /// Create a box spanning between two corners, in any order
+    pub fn new(a: Vector3, b: Vector3) -> Self{
+        Self{
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+    /// Create a box of `size` centered on `center`
+    pub fn from_center(center: Vector3, size: Vector3) -> Self{
+        let half = size.abs() / 2.0;
+        Self{
+            min: center - half,
+            max: center + half,
+        }
+    }
+    /// Create the smallest box holding all the `points`, `None` if there are none
+    pub fn from_points(points: impl IntoIterator<Item = Vector3>) -> Option<Self>{
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Self::new(first, first), |out, point| out.expand_to(point)))
+    }
+    pub fn center(&self) -> Vector3{
+        (self.min + self.max) / 2.0
+    }
+    pub fn size(&self) -> Vector3{
+        self.max - self.min
+    }
+    pub fn volume(&self) -> f32{
+        let size = self.size();
+        size.x * size.y * size.z
+    }
+    /// Check if `point` is inside the box, faces included
+    pub fn contains_point(&self, point: Vector3) -> bool{
+        self.min.x <= point.x && point.x <= self.max.x
+        && self.min.y <= point.y && point.y <= self.max.y
+        && self.min.z <= point.z && point.z <= self.max.z
+    }
+    /// Check if `other` is entirely inside the box
+    pub fn contains(&self, other: &Self) -> bool{
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+    /// Check if the boxes overlap, touching faces count
+    pub fn intersects(&self, other: &Self) -> bool{
+        self.min.x <= other.max.x && other.min.x <= self.max.x
+        && self.min.y <= other.max.y && other.min.y <= self.max.y
+        && self.min.z <= other.max.z && other.min.z <= self.max.z
+    }
+    /// Returns the overlapping space, or `None` if the boxes don't overlap
+    pub fn intersection(&self, other: &Self) -> Option<Self>{
+        if !self.intersects(other){
+            return None
+        }
+        Some(Self{
+            min: self.min.max(other.min),
+            max: self.max.min(other.max),
+        })
+    }
+    /// Returns the smallest box holding both boxes
+    pub fn union(&self, other: &Self) -> Self{
+        Self{
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+    /// Grow the box by `amount` on every side, negative `amount` shrinks it down to it's center
+    pub fn expand(&self, amount: f32) -> Self{
+        let center = self.center();
+        Self{
+            min: (self.min - Vector3::new(amount, amount, amount)).min(center),
+            max: (self.max + Vector3::new(amount, amount, amount)).max(center),
+        }
+    }
+    /// Grow the box just enough to hold `point`
+    pub fn expand_to(&self, point: Vector3) -> Self{
+        Self{
+            min: self.min.min(point),
+            max: self.max.max(point),
+        }
+    }
+    /// Returns the point in the box closest to `point`, `point` itself if it's inside
+    pub fn closest_point(&self, point: Vector3) -> Vector3{
+        point.clamp(self.min, self.max)
+    }
+}
+
+/// # Circle
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Circle{
+    pub center: Vector2,
+    pub radius: f32
+}
+impl Circle{
+    pub fn new(center: Vector2, radius: f32) -> Self{
+        Self{center, radius: radius.abs()}
+    }
+    /// Returns the smallest box holding the Circle
+    pub fn bounds(&self) -> Aabb2{
+        Aabb2::from_center(self.center, Vector2::new(self.radius, self.radius) * 2.0)
+    }
+    pub fn area(&self) -> f32{
+        std::f32::consts::PI * self.radius * self.radius
+    }
+    /// Check if `point` is inside the Circle, edge included
+    pub fn contains_point(&self, point: Vector2) -> bool{
+        (point - self.center).magnitude_squared() <= self.radius * self.radius
+    }
+    /// Check if `other` is entirely inside the Circle
+    pub fn contains(&self, other: &Self) -> bool{
+        self.center.distance(other.center) + other.radius <= self.radius
+    }
+    /// Check if the Circles overlap, touching edges count
+    pub fn intersects(&self, other: &Self) -> bool{
+        let reach = self.radius + other.radius;
+        (self.center - other.center).magnitude_squared() <= reach * reach
+    }
+    /// Check if the Circle overlaps a box, touching edges count
+    pub fn intersects_aabb(&self, aabb: &Aabb2) -> bool{
+        self.contains_point(aabb.closest_point(self.center))
+    }
+    /// Returns the smallest Circle holding both Circles
+    pub fn union(&self, other: &Self) -> Self{
+        if self.contains(other){
+            return *self
+        }
+        if other.contains(self){
+            return *other
+        }
+        let offset = other.center - self.center;
+        let distance = offset.magnitude();
+        let radius = (distance + self.radius + other.radius) / 2.0;
+        // Neither holds the other, so the centers can't be in the same spot
+        Self{
+            center: self.center + offset / distance * (radius - self.radius),
+            radius,
+        }
+    }
+    /// Grow the radius by `amount`, never going below 0
+    pub fn expand(&self, amount: f32) -> Self{
+        Self{
+            center: self.center,
+            radius: (self.radius + amount).max(0.0),
+        }
+    }
+    /// Returns the point in the Circle closest to `point`, `point` itself if it's inside
+    pub fn closest_point(&self, point: Vector2) -> Vector2{
+        if self.contains_point(point){
+            return point
+        }
+        self.center + (point - self.center).normalize_or_zero() * self.radius
+    }
+}
+
+/// # Sphere
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Sphere{
+    pub center: Vector3,
+    pub radius: f32
+}
+impl Sphere{
+    pub fn new(center: Vector3, radius: f32) -> Self{
+        Self{center, radius: radius.abs()}
+    }
+    /// Returns the smallest box holding the Sphere
+    pub fn bounds(&self) -> Aabb3{
+        Aabb3::from_center(self.center, Vector3::new(self.radius, self.radius, self.radius) * 2.0)
+    }
+    pub fn volume(&self) -> f32{
+        4.0 / 3.0 * std::f32::consts::PI * self.radius * self.radius * self.radius
+    }
+    /// Check if `point` is inside the Sphere, surface included
+    pub fn contains_point(&self, point: Vector3) -> bool{
+        (point - self.center).magnitude_squared() <= self.radius * self.radius
+    }
+    /// Check if `other` is entirely inside the Sphere
+    pub fn contains(&self, other: &Self) -> bool{
+        self.center.distance(other.center) + other.radius <= self.radius
+    }
+    /// Check if the Spheres overlap, touching surfaces count
+    pub fn intersects(&self, other: &Self) -> bool{
+        let reach = self.radius + other.radius;
+        (self.center - other.center).magnitude_squared() <= reach * reach
+    }
+    /// Check if the Sphere overlaps a box, touching surfaces count
+    pub fn intersects_aabb(&self, aabb: &Aabb3) -> bool{
+        self.contains_point(aabb.closest_point(self.center))
+    }
+    /// Returns the smallest Sphere holding both Spheres
+    pub fn union(&self, other: &Self) -> Self{
+        if self.contains(other){
+            return *self
+        }
+        if other.contains(self){
+            return *other
+        }
+        let offset = other.center - self.center;
+        let distance = offset.magnitude();
+        let radius = (distance + self.radius + other.radius) / 2.0;
+        // Neither holds the other, so the centers can't be in the same spot
+        Self{
+            center: self.center + offset / distance * (radius - self.radius),
+            radius,
+        }
+    }
+    /// Grow the radius by `amount`, never going below 0
+    pub fn expand(&self, amount: f32) -> Self{
+        Self{
+            center: self.center,
+            radius: (self.radius + amount).max(0.0),
+        }
+    }
+    /// Returns the point in the Sphere closest to `point`, `point` itself if it's inside
+    pub fn closest_point(&self, point: Vector3) -> Vector3{
+        if self.contains_point(point){
+            return point
+        }
+        self.center + (point - self.center).normalize_or_zero() * self.radius
+    }
+}
+
+/// # 2D Ray
+/// A half-line going from `origin` towards `dir` forever
+///
+/// Intersection queries return the distance along the Ray to the first hit, use `at` to get the point.
+/// A Ray starting inside a shape hits it at distance 0
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Ray2{
+    pub origin: Vector2,
+    dir: Vector2
+}
+impl Ray2{
+    /// Create a new Ray, `dir` gets normalized. A zero `dir` makes a Ray that only hits it's origin
+    pub fn new(origin: Vector2, dir: Vector2) -> Self{
+        Self{origin, dir: dir.normalize_or_zero()}
+    }
+    /// Create a Ray going from `origin` through `target`
+    pub fn towards(origin: Vector2, target: Vector2) -> Self{
+        Self::new(origin, target - origin)
+    }
+    /// Returns the normalized direction
+    pub fn dir(&self) -> Vector2{
+        self.dir
+    }
+    /// Returns the point `distance` along the Ray
+    pub fn at(&self, distance: f32) -> Vector2{
+        self.origin + self.dir * distance
+    }
+    /// Returns the point on the Ray closest to `point`
+    pub fn closest_point(&self, point: Vector2) -> Vector2{
+        self.at((point - self.origin).dot(self.dir).max(0.0))
+    }
+    /// Returns the distance to where the Ray enters the box
+    pub fn intersect_aabb(&self, aabb: &Aabb2) -> Option<f32>{
+        slab_intersection(&[self.origin.x, self.origin.y], &[self.dir.x, self.dir.y], &[aabb.min.x, aabb.min.y], &[aabb.max.x, aabb.max.y])
+    }
+    /// Returns the distance to where the Ray enters the Circle
+    pub fn intersect_circle(&self, circle: &Circle) -> Option<f32>{
+        let offset = self.origin - circle.center;
+        sphere_intersection(offset.dot(self.dir), offset.magnitude_squared(), circle.radius)
+    }
+    /// Returns the distance to where the Ray crosses the Segment
+    pub fn intersect_segment(&self, segment: &Segment2) -> Option<f32>{
+        let edge = segment.end - segment.start;
+        let denom = cross_2d(self.dir, edge);
+        if denom.abs() < f32::EPSILON{
+            // Parallel, only counts if the Segment is right on the Ray
+            let closest = self.closest_point(segment.closest_point(self.origin));
+            return match segment.distance_to_point(closest) < 1e-5{
+                true => Some((closest - self.origin).magnitude()),
+                false => None,
+            }
+        }
+        let to_start = segment.start - self.origin;
+        let distance = cross_2d(to_start, edge) / denom;
+        let along = cross_2d(to_start, self.dir) / denom;
+        match distance >= 0.0 && (0.0..=1.0).contains(&along){
+            true => Some(distance),
+            false => None,
+        }
+    }
+}
+
+/// # 3D Ray
+/// A half-line going from `origin` towards `dir` forever
+///
+/// Intersection queries return the distance along the Ray to the first hit, use `at` to get the point.
+/// A Ray starting inside a shape hits it at distance 0
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Ray3{
+    pub origin: Vector3,
+    dir: Vector3
+}
+impl Ray3{
+    /// Create a new Ray, `dir` gets normalized. A zero `dir` makes a Ray that only hits it's origin
+    pub fn new(origin: Vector3, dir: Vector3) -> Self{
+        Self{origin, dir: dir.normalize_or_zero()}
+    }
+    /// Create a Ray going from `origin` through `target`
+    pub fn towards(origin: Vector3, target: Vector3) -> Self{
+        Self::new(origin, target - origin)
+    }
+    /// Returns the normalized direction
+    pub fn dir(&self) -> Vector3{
+        self.dir
+    }
+    /// Returns the point `distance` along the Ray
+    pub fn at(&self, distance: f32) -> Vector3{
+        self.origin + self.dir * distance
+    }
+    /// Returns the point on the Ray closest to `point`
+    pub fn closest_point(&self, point: Vector3) -> Vector3{
+        self.at((point - self.origin).dot(self.dir).max(0.0))
+    }
+    /// Returns the distance to where the Ray enters the box
+    pub fn intersect_aabb(&self, aabb: &Aabb3) -> Option<f32>{
+        slab_intersection(
+            &[self.origin.x, self.origin.y, self.origin.z],
+            &[self.dir.x, self.dir.y, self.dir.z],
+            &[aabb.min.x, aabb.min.y, aabb.min.z],
+            &[aabb.max.x, aabb.max.y, aabb.max.z]
+        )
+    }
+    /// Returns the distance to where the Ray enters the Sphere
+    pub fn intersect_sphere(&self, sphere: &Sphere) -> Option<f32>{
+        let offset = self.origin - sphere.center;
+        sphere_intersection(offset.dot(self.dir), offset.magnitude_squared(), sphere.radius)
+    }
+}
+
+/// # 2D Line Segment
+/// A straight line between two points
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Segment2{
+    pub start: Vector2,
+    pub end: Vector2
+}
+impl Segment2{
+    pub fn new(start: Vector2, end: Vector2) -> Self{
+        Self{start, end}
+    }
+    pub fn length(&self) -> f32{
+        self.start.distance(self.end)
+    }
+    /// Returns the smallest box holding the Segment
+    pub fn bounds(&self) -> Aabb2{
+        Aabb2::new(self.start, self.end)
+    }
+    /// Returns the point on the Segment closest to `point`
+    pub fn closest_point(&self, point: Vector2) -> Vector2{
+        let edge = self.end - self.start;
+        let length = edge.magnitude_squared();
+        if length == 0.0{
+            return self.start
+        }
+        self.start + edge * ((point - self.start).dot(edge) / length).clamp(0.0, 1.0)
+    }
+    pub fn distance_to_point(&self, point: Vector2) -> f32{
+        self.closest_point(point).distance(point)
+    }
+    /// Returns where the Segments cross, or `None` if they don't
+    ///
+    /// Overlapping parallel Segments return the point of `other` closest to `self.start`
+    pub fn intersection(&self, other: &Self) -> Option<Vector2>{
+        let distance = Ray2::towards(self.start, self.end).intersect_segment(other)?;
+        match distance <= self.length() + 1e-5{
+            true => Some(Ray2::towards(self.start, self.end).at(distance)),
+            false => None,
+        }
+    }
+    pub fn intersects(&self, other: &Self) -> bool{
+        self.intersection(other).is_some()
+    }
+}
+
+/// # 3D Line Segment
+/// A straight line between two points
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Segment3{
+    pub start: Vector3,
+    pub end: Vector3
+}
+impl Segment3{
+    pub fn new(start: Vector3, end: Vector3) -> Self{
+        Self{start, end}
+    }
+    pub fn length(&self) -> f32{
+        self.start.distance(self.end)
+    }
+    /// Returns the smallest box holding the Segment
+    pub fn bounds(&self) -> Aabb3{
+        Aabb3::new(self.start, self.end)
+    }
+    /// Returns the point on the Segment closest to `point`
+    pub fn closest_point(&self, point: Vector3) -> Vector3{
+        let edge = self.end - self.start;
+        let length = edge.magnitude_squared();
+        if length == 0.0{
+            return self.start
+        }
+        self.start + edge * ((point - self.start).dot(edge) / length).clamp(0.0, 1.0)
+    }
+    pub fn distance_to_point(&self, point: Vector3) -> f32{
+        self.closest_point(point).distance(point)
+    }
+}
+
+fn cross_2d(a: Vector2, b: Vector2) -> f32{
+    a.x * b.y - a.y * b.x
+}
+
+/// Ray vs box test, one axis at a time
+fn slab_intersection(origin: &[f32], dir: &[f32], min: &[f32], max: &[f32]) -> Option<f32>{
+    let (mut near, mut far) = (0.0f32, f32::INFINITY);
+    for axis in 0..origin.len(){
+        if dir[axis] == 0.0{
+            // Parallel to this slab, it's either always in or never in
+            if origin[axis] < min[axis] || origin[axis] > max[axis]{
+                return None
+            }
+            continue;
+        }
+        let a = (min[axis] - origin[axis]) / dir[axis];
+        let b = (max[axis] - origin[axis]) / dir[axis];
+        near = near.max(a.min(b));
+        far = far.min(a.max(b));
+        if near > far{
+            return None
+        }
+    }
+    Some(near)
+}
+
+/// Ray vs circle or sphere test, takes `offset.dot(dir)` and `offset.magnitude_squared()` of the Ray's origin from the center
+fn sphere_intersection(along: f32, distance_squared: f32, radius: f32) -> Option<f32>{
+    let outside = distance_squared - radius * radius;
+    // Starts outside and points away
+    if outside > 0.0 && along > 0.0{
+        return None
+    }
+    let discriminant = along * along - outside;
+    if discriminant < 0.0{
+        return None
+    }
+    Some((-along - discriminant.sqrt()).max(0.0))
+}
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::*;
+use crate::commands::{spawn_entity, spawned};
+use crate::resources::SnapshotRegistry;
+
+/// Version of the `WorldSnapshot` layout itself, bumped when the file structure changes
+pub const SNAPSHOT_FORMAT: u32 = 1;
+
+/// Upgrades saved data of a Component or Resource by exactly one version
+///
+/// Gets the data as it was saved and returns it in the layout of the next version,
+/// or a message explaining why it can't be upgraded
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+/// Errors that can occur when saving or loading a `WorldSnapshot`
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotError{
+    /// A Snapshot file could not be read or written
+    Io{
+        file: String,
+        message: String
+    },
+    /// The Snapshot is not valid JSON or doesn't have the right layout
+    Parse(String),
+    /// The Snapshot was made by a newer version of the library
+    UnsupportedFormat{
+        found: u32,
+        supported: u32
+    },
+    /// The Snapshot has a Component that is not registered in `SnapshotRegistry`
+    UnknownComponent(String),
+    /// The Snapshot has a Resource that is not registered in `SnapshotRegistry`
+    UnknownResource(String),
+    /// The data was saved with a newer version than the registered one
+    NewerVersion{
+        id: String,
+        found: u32,
+        supported: u32
+    },
+    /// There's no Migration to upgrade the data from this version
+    MissingMigration{
+        id: String,
+        from: u32
+    },
+    /// A Migration refused the data
+    Migration{
+        id: String,
+        from: u32,
+        message: String
+    },
+    /// A Component or Resource could not be turned into or out of JSON
+    Serde{
+        id: String,
+        message: String
+    },
+}
+impl std::fmt::Display for SnapshotError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            SnapshotError::Io{file, message} => f.write_fmt(format_args!("{}: {}", file, message)),
+            SnapshotError::Parse(message) => f.write_fmt(format_args!("Invalid Snapshot: {}", message)),
+            SnapshotError::UnsupportedFormat{found, supported} => f.write_fmt(format_args!("Snapshot format {} is newer than the supported {}", found, supported)),
+            SnapshotError::UnknownComponent(id) => f.write_fmt(format_args!("Component `{}` is not registered for Snapshots", id)),
+            SnapshotError::UnknownResource(id) => f.write_fmt(format_args!("Resource `{}` is not registered for Snapshots", id)),
+            SnapshotError::NewerVersion{id, found, supported} => f.write_fmt(format_args!("`{}` was saved with version {}, but only {} is supported", id, found, supported)),
+            SnapshotError::MissingMigration{id, from} => f.write_fmt(format_args!("`{}` has no Migration from version {}", id, from)),
+            SnapshotError::Migration{id, from, message} => f.write_fmt(format_args!("`{}` failed to migrate from version {}: {}", id, from, message)),
+            SnapshotError::Serde{id, message} => f.write_fmt(format_args!("`{}`: {}", id, message)),
+        }
+    }
+}
+impl std::error::Error for SnapshotError{}
+
+/// A single saved Entity
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct EntityRecord{
+    /// ID the Entity had when it was saved, only meaningful within the same Snapshot
+    pub id: usize,
+    /// Saved Components, keyed by the Component's `ID`
+    pub components: BTreeMap<String, Value>
+}
+
+/// # World Snapshot
+/// Saved state of every Entity with Components registered in `SnapshotRegistry` Resource,
+/// along with the Resources registered there
+///
+/// Components and Resources are keyed by their `ID`s, so those have to stay the same between versions of your game.
+/// Each of them also has it's version recorded, older data gets upgraded with Migrations when restoring,
+/// see `SnapshotRegistry::add_component_migration`
+///
+/// Entities without any registered Components are not saved.
+/// Hierarchy links are not saved either, as Tokens don't survive a reload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldSnapshot{
+    /// Layout version of the Snapshot itself, see `SNAPSHOT_FORMAT`
+    pub format: u32,
+    /// Version each Component was saved with, missing ones count as version 1
+    #[serde(default)]
+    pub component_versions: BTreeMap<String, u32>,
+    /// Version each Resource was saved with, missing ones count as version 1
+    #[serde(default)]
+    pub resource_versions: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub entities: Vec<EntityRecord>,
+    /// Saved Resources, keyed by the Resource's `ID`
+    #[serde(default)]
+    pub resources: BTreeMap<String, Value>
+}
+impl WorldSnapshot{
+    /// Create an empty Snapshot
+    pub fn new() -> Self{
+        Self{
+            format: SNAPSHOT_FORMAT,
+            component_versions: BTreeMap::new(),
+            resource_versions: BTreeMap::new(),
+            entities: Vec::new(),
+            resources: BTreeMap::new(),
+        }
+    }
+    /// Save the World using the `SnapshotRegistry` Resource
+    pub fn capture(world: &World) -> Result<Self, SnapshotError>{
+        world.fetch_res::<SnapshotRegistry>().capture(world)
+    }
+    /// Spawn every saved Entity into the World and overwrite the saved Resources
+    ///
+    /// Old data is migrated and everything is checked before the World is touched,
+    /// so a failed restore leaves the World as it was.
+    ///
+    /// Existing Entities are left alone, despawn them first if you're loading a save over the current game.
+    /// Sends `EntitySpawned` Event for every spawned Entity and indexes their Tags, see `TagIndex`
+    ///
+    /// Returns Tokens of the spawned Entities, keyed by the IDs they had when saved
+    pub fn restore(self, world: &mut World) -> Result<BTreeMap<usize, Token>, SnapshotError>{
+        let decoded = world.fetch_res::<SnapshotRegistry>().decode(self)?;
+
+        let mut tokens = BTreeMap::new();
+        for (old_id, inserters) in decoded.entities{
+            let token = spawn_entity(world).get_token();
+            for insert in inserters{
+                insert(world, token.id());
+            }
+            spawned(world, token);
+            tokens.insert(old_id, token);
+        }
+        for insert in decoded.resources{
+            insert(world);
+        }
+        Ok(tokens)
+    }
+    /// Turn the Snapshot into pretty JSON
+    pub fn to_json(&self) -> Result<String, SnapshotError>{
+        serde_json::to_string_pretty(self).map_err(|err| SnapshotError::Parse(err.to_string()))
+    }
+    /// Read a Snapshot from JSON
+    ///
+    /// Fails on Snapshots from a newer format
+    pub fn from_json(source: &str) -> Result<Self, SnapshotError>{
+        let snapshot: Self = serde_json::from_str(source).map_err(|err| SnapshotError::Parse(err.to_string()))?;
+        if snapshot.format > SNAPSHOT_FORMAT{
+            return Err(SnapshotError::UnsupportedFormat{found: snapshot.format, supported: SNAPSHOT_FORMAT})
+        }
+        Ok(snapshot)
+    }
+    /// Write the Snapshot to a file as JSON
+    pub fn save_file(&self, path: impl AsRef<std::path::Path>) -> Result<(), SnapshotError>{
+        std::fs::write(path.as_ref(), self.to_json()?).map_err(|err| SnapshotError::Io{
+            file: path.as_ref().display().to_string(),
+            message: err.to_string(),
+        })
+    }
+    /// Read a Snapshot from a JSON file
+    pub fn load_file(path: impl AsRef<std::path::Path>) -> Result<Self, SnapshotError>{
+        let source = std::fs::read_to_string(path.as_ref()).map_err(|err| SnapshotError::Io{
+            file: path.as_ref().display().to_string(),
+            message: err.to_string(),
+        })?;
+        Self::from_json(&source)
+    }
+}
+impl Default for WorldSnapshot{
+    fn default() -> Self {
+        Self::new()
+    }
+}
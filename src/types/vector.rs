@@ -6,65 +6,246 @@ use std::ops::{
     Mul,
     MulAssign,
     Div,
-    DivAssign
+    DivAssign,
+    Neg,
+    Index,
+    IndexMut
 };
-/// A simple 2D coordinate type
-#[derive(Clone, Copy)]
-pub struct Vector2{
-    pub x: f32,
-    pub y: f32
+
+use super::number::*;
+
+/// A 2D float coordinate type
+pub type Vector2 = Vec2<f32>;
+/// A 2D double precision coordinate type
+pub type Vector2d = Vec2<f64>;
+/// A 2D integer coordinate type, for terminal cells and grid positions
+pub type Vector2i = Vec2<i32>;
+/// A 2D unsigned integer coordinate type, for sizes and grid positions that can't go negative
+pub type Vector2u = Vec2<u32>;
+
+/// A 3D float coordinate type
+pub type Vector3 = Vec3<f32>;
+/// A 3D double precision coordinate type
+pub type Vector3d = Vec3<f64>;
+/// A 3D integer coordinate type, for grid positions
+pub type Vector3i = Vec3<i32>;
+/// A 3D unsigned integer coordinate type, for sizes and grid positions that can't go negative
+pub type Vector3u = Vec3<u32>;
+
+/// How a float gets turned into an integer
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rounding{
+    /// Towards negative infinity
+    Floor,
+    /// Towards positive infinity
+    Ceil,
+    /// To the nearest integer, halves away from zero
+    Round,
+    /// Towards zero
+    Trunc,
+}
+impl Rounding{
+    /// Round a float according to the mode
+    pub fn apply(self, value: f32) -> f32{
+        match self{
+            Rounding::Floor => value.floor(),
+            Rounding::Ceil => value.ceil(),
+            Rounding::Round => value.round(),
+            Rounding::Trunc => value.trunc(),
+        }
+    }
 }
-impl Vector2{
+
+// `PartialOrd` has no `min`/`max`, and floats aren't `Ord`
+fn lesser<T: PartialOrd>(a: T, b: T) -> T{
+    if b < a {b} else {a}
+}
+fn greater<T: PartialOrd>(a: T, b: T) -> T{
+    if b > a {b} else {a}
+}
+
+/// A simple 2D coordinate type, generic over it's number type
+///
+/// You'll usually want one of the aliases: `Vector2`, `Vector2d`, `Vector2i` or `Vector2u`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vec2<T>{
+    pub x: T,
+    pub y: T
+}
+impl<T: Number> Vec2<T>{
     /// Create a new 2D Vector
-    pub fn new(x: f32, y: f32) -> Self {
+    pub fn new(x: T, y: T) -> Self {
         Self{x, y}
     }
     /// Returns the dot product of `self` and `other`
-    /// 
+    ///
     /// Simpler explanation: How similar the two Vectors are
     /// - Sign defines direction, positive means same direction, negative means opposite direction
     /// - Value defines how similar the magnitude is
-    pub fn dot(self, other: Self) -> f32{
+    pub fn dot(self, other: Self) -> T{
         (self.x * other.x) + (self.y * other.y)
     }
+    /// Convert each axis to a different number type
+    ///
+    /// Works like `as` on each axis, see `Number::from_number`
+    pub fn cast<U: Number>(self) -> Vec2<U>{
+        Vec2::new(U::from_number(self.x), U::from_number(self.y))
+    }
+    /// Convert to a float Vector
+    pub fn to_vector(self) -> Vector2{
+        Vector2::new(self.x.to_f32(), self.y.to_f32())
+    }
+    /// Returns the length of `self` squared
+    ///
+    /// Cheaper than `magnitude`, good enough for comparing lengths
+    pub fn magnitude_squared(self) -> T{
+        self.dot(self)
+    }
+    /// Returns the smaller value of each axis
+    pub fn min(self, other: Self) -> Self{
+        Self::new(lesser(self.x, other.x), lesser(self.y, other.y))
+    }
+    /// Returns the bigger value of each axis
+    pub fn max(self, other: Self) -> Self{
+        Self::new(greater(self.x, other.x), greater(self.y, other.y))
+    }
+    /// Returns `self` with each axis kept between `low` and `high`
+    pub fn clamp(self, low: Self, high: Self) -> Self{
+        self.max(low).min(high)
+    }
+}
+impl<T: Signed> Vec2<T>{
+    /// Returns the absolute value of each axis
+    pub fn abs(self) -> Self{
+        Self::new(self.x.abs(), self.y.abs())
+    }
+    /// Returns `self` rotated by 90° counter-clockwise
+    pub fn perp(self) -> Self{
+        Self::new(-self.y, self.x)
+    }
+}
+impl<T: Float> Vec2<T>{
+    /// Create a Vector of magnitude 1 pointing at `angle` Radians counter-clockwise from +X
+    pub fn from_angle(angle: T) -> Self{
+        let (sin, cos) = angle.sin_cos();
+        Self::new(cos, sin)
+    }
+    /// Returns the angle of `self` in Radians counter-clockwise from +X
+    pub fn angle(self) -> T{
+        self.y.atan2(self.x)
+    }
+    /// Returns `self` rotated by `angle` Radians counter-clockwise
+    pub fn rotate(self, angle: T) -> Self{
+        let (sin, cos) = angle.sin_cos();
+        Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+    /// Returns a point `t` of the way from `self` to `other` along a straight line
+    ///
+    /// `t` of 0 gives `self`, 1 gives `other`, values outside of that extrapolate
+    pub fn lerp(self, other: Self, t: T) -> Self{
+        self + (other - self) * t
+    }
+    /// Returns a Vector `t` of the way from `self` to `other` along an arc
+    ///
+    /// Direction turns at a constant rate the shorter way around, magnitude changes linearly
+    pub fn slerp(self, other: Self, t: T) -> Self{
+        let (from, to) = (self.magnitude(), other.magnitude());
+        if from == T::ZERO || to == T::ZERO{
+            return self.lerp(other, t)
+        }
+        let cross = self.x * other.y - self.y * other.x;
+        let turn = cross.atan2(self.dot(other));
+        Self::from_angle(self.angle() + turn * t) * (from + (to - from) * t)
+    }
     /// Returns a Vector that's a projection of `self` onto `other`
-    /// 
+    ///
     /// Simpler explanation: It flattens `self` onto `other`
+    ///
+    /// Projecting onto a zero Vector gives a zero Vector
     pub fn project(self, other: Self) -> Self{
-        let scalar = self.dot(other)/other.magnitude().powi(2);
-        self * scalar
+        let length = other.magnitude_squared();
+        if length == T::ZERO{
+            return Self::default()
+        }
+        other * (self.dot(other) / length)
     }
     /// Returns a reflection of `self` around `other`
     pub fn reflect(self, other: Self) -> Self{
-        self.project(other) * 2.0 - self
+        self.project(other) * (T::ONE + T::ONE) - self
     }
     /// Returns distance between endpoint of `self` and `other`
-    pub fn distance(self, other: Self) -> f32{
+    pub fn distance(self, other: Self) -> T{
         (self - other).magnitude()
     }
     /// Returns self normalized to magnitude of 1
+    ///
+    /// ## WARNING
+    /// A zero Vector has no direction and gives NaN, use `try_normalize` or `normalize_or_zero` if it can be zero
     pub fn normalize(self) -> Self{
         self / self.magnitude()
     }
+    /// Returns self normalized to magnitude of 1, or `None` if it's a zero Vector or not finite
+    pub fn try_normalize(self) -> Option<Self>{
+        let magnitude = self.magnitude();
+        // NaN fails the comparison too, an infinite magnitude would turn the axes into NaN
+        match magnitude > T::ZERO && magnitude.is_finite(){
+            true => Some(self / magnitude),
+            false => None,
+        }
+    }
+    /// Returns self normalized to magnitude of 1, or a zero Vector if it's a zero Vector
+    pub fn normalize_or_zero(self) -> Self{
+        self.try_normalize().unwrap_or_default()
+    }
     /// Returns the angle between `self` and `other` in Radians
-    pub fn angle_between(self, other: Self) -> f32{
-        (self.dot(other) / (self.magnitude() * other.magnitude())).acos()
+    ///
+    /// Zero Vectors have no direction, so it's 0 if either of them is one
+    pub fn angle_between(self, other: Self) -> T{
+        let magnitude = self.magnitude() * other.magnitude();
+        if magnitude == T::ZERO{
+            return T::ZERO
+        }
+        // Rounding can push the cosine just past 1, where `acos` is NaN
+        let cos = self.dot(other) / magnitude;
+        greater(lesser(cos, T::ONE), -T::ONE).acos()
+    }
+    /// Returns `true` if every axis of `self` is within `epsilon` of `other`
+    pub fn approx_eq(self, other: Self, epsilon: T) -> bool{
+        let diff = (self - other).abs();
+        diff.x <= epsilon && diff.y <= epsilon
     }
     /// Returns the length of `self`
-    /// 
-    /// ## WARNING
-    /// Because this uses Pythagorean Theorem to calculate, a Vector too big may cause an overflow, and subsequently a crash
-    pub fn magnitude(self) -> f32{
-        (self.x.powi(2) + self.y.powi(2)).sqrt()
+    ///
+    /// Uses `hypot`, so big Vectors don't overflow halfway through
+    pub fn magnitude(self) -> T{
+        self.x.hypot(self.y)
+    }
+}
+impl<T: Integer> Vec2<T>{
+    /// Returns the grid distance when moving only along the axes
+    ///
+    /// Distances too big for `T::Unsigned` stop at it's maximum
+    pub fn manhattan(self, other: Self) -> T::Unsigned{
+        self.x.abs_diff(other.x).saturating_add(self.y.abs_diff(other.y))
+    }
+    /// Returns the grid distance when diagonal moves are allowed
+    pub fn chebyshev(self, other: Self) -> T::Unsigned{
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+    /// Convert a float Vector using the given rounding mode
+    ///
+    /// Values out of range are clamped to the type's bounds
+    pub fn from_vector(vector: Vector2, rounding: Rounding) -> Self{
+        Self::new(T::from_f32(rounding.apply(vector.x)), T::from_f32(rounding.apply(vector.y)))
     }
-
 }
-impl std::fmt::Display for Vector2{
+impl<T: Number + std::fmt::Display> std::fmt::Display for Vec2<T>{
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!("({}, {})", self.x, self.y))
     }
 }
-impl Add for Vector2{
+impl<T: Number> Add for Vec2<T>{
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -74,13 +255,13 @@ impl Add for Vector2{
         }
     }
 }
-impl AddAssign for Vector2{
+impl<T: Number> AddAssign for Vec2<T>{
     fn add_assign(&mut self, rhs: Self) {
         self.x += rhs.x;
         self.y += rhs.y;
     }
 }
-impl Sub for Vector2{
+impl<T: Number> Sub for Vec2<T>{
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
@@ -90,106 +271,165 @@ impl Sub for Vector2{
         }
     }
 }
-impl SubAssign for Vector2{
+impl<T: Number> SubAssign for Vec2<T>{
     fn sub_assign(&mut self, rhs: Self) {
         self.x -= rhs.x;
         self.y -= rhs.y
     }
 }
-impl Mul<f32> for Vector2{
+impl<T: Number> Mul<T> for Vec2<T>{
     type Output = Self;
 
-    fn mul(self, rhs: f32) -> Self::Output {
+    fn mul(self, rhs: T) -> Self::Output {
         Self{
             x: self.x * rhs,
             y: self.y * rhs
         }
     }
 }
-impl MulAssign<f32> for Vector2{
-    fn mul_assign(&mut self, rhs: f32) {
+impl<T: Number> MulAssign<T> for Vec2<T>{
+    fn mul_assign(&mut self, rhs: T) {
         self.x *= rhs;
         self.y *= rhs;
     }
 }
-impl Mul<Vector2> for Vector2{
+impl<T: Number> Mul<Vec2<T>> for Vec2<T>{
     type Output = Self;
 
-    fn mul(self, rhs: Vector2) -> Self::Output {
+    fn mul(self, rhs: Vec2<T>) -> Self::Output {
         Self{
             x: self.x * rhs.x,
             y: self.y * rhs.y,
         }
     }
 }
-impl MulAssign<Vector2> for Vector2{
-    fn mul_assign(&mut self, rhs: Vector2) {
+impl<T: Number> MulAssign<Vec2<T>> for Vec2<T>{
+    fn mul_assign(&mut self, rhs: Vec2<T>) {
         self.x *= rhs.x;
         self.y *= rhs.y;
     }
 }
-impl Div<f32> for Vector2{
+impl<T: Number> Div<T> for Vec2<T>{
     type Output = Self;
 
-    fn div(self, rhs: f32) -> Self::Output {
+    fn div(self, rhs: T) -> Self::Output {
         Self{
             x: self.x / rhs,
             y: self.y / rhs,
         }
     }
 }
-impl DivAssign<f32> for Vector2 {
-    fn div_assign(&mut self, rhs: f32) {
+impl<T: Number> DivAssign<T> for Vec2<T> {
+    fn div_assign(&mut self, rhs: T) {
         self.x /= rhs;
         self.y /= rhs;
     }
 }
-impl Div<Vector2> for Vector2{
+impl<T: Number> Div<Vec2<T>> for Vec2<T>{
     type Output = Self;
 
-    fn div(self, rhs: Vector2) -> Self::Output {
+    fn div(self, rhs: Vec2<T>) -> Self::Output {
         Self{
             x: self.x / rhs.x,
             y: self.y / rhs.y,
         }
     }
 }
-impl DivAssign<Vector2> for Vector2{
-    fn div_assign(&mut self, rhs: Vector2) {
+impl<T: Number> DivAssign<Vec2<T>> for Vec2<T>{
+    fn div_assign(&mut self, rhs: Vec2<T>) {
         self.x /= rhs.x;
         self.y /= rhs.y;
     }
 }
 
-/// A simple 3D coordinate type
-#[derive(Clone, Copy)]
-pub struct Vector3{
-    pub x: f32,
-    pub y: f32,
-    pub z: f32
-}
-impl Vector3{
-    /// Create a new 2D Vector
-    pub fn new(x: f32, y: f32, z: f32) -> Self {
+impl<T: Signed> Neg for Vec2<T>{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+impl<T> Index<usize> for Vec2<T>{
+    type Output = T;
+
+    /// Axis by index, `0` is X and `1` is Y
+    fn index(&self, index: usize) -> &Self::Output {
+        match index{
+            0 => &self.x,
+            1 => &self.y,
+            _ => panic!("Vec2 index out of bounds: {index}")
+        }
+    }
+}
+impl<T> IndexMut<usize> for Vec2<T>{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match index{
+            0 => &mut self.x,
+            1 => &mut self.y,
+            _ => panic!("Vec2 index out of bounds: {index}")
+        }
+    }
+}
+
+/// A simple 3D coordinate type, generic over it's number type
+///
+/// You'll usually want one of the aliases: `Vector3`, `Vector3d`, `Vector3i` or `Vector3u`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vec3<T>{
+    pub x: T,
+    pub y: T,
+    pub z: T
+}
+impl<T: Number> Vec3<T>{
+    /// Create a new 3D Vector
+    pub fn new(x: T, y: T, z: T) -> Self {
         Self{x, y, z}
     }
     /// Returns the dot product of `self` and `other`
-    /// 
+    ///
     /// Simpler explanation: How similar the two Vectors are
     /// - Sign defines direction, positive means same direction, negative means opposite direction
     /// - Value defines how similar the magnitude is, 0 means perpendicular
-    pub fn dot(self, other: Self) -> f32{
+    pub fn dot(self, other: Self) -> T{
         (self.x * other.x) + (self.y * other.y) + (self.z * other.z)
     }
-    /// Returns a Vector that's a projection of `self` onto `other`
-    /// 
-    /// Simpler explanation: It flattens `self` onto `other`
-    pub fn project(self, other: Self) -> Self{
-        let scalar = self.dot(other)/other.magnitude().powi(2);
-        self * scalar
+    /// Convert each axis to a different number type
+    ///
+    /// Works like `as` on each axis, see `Number::from_number`
+    pub fn cast<U: Number>(self) -> Vec3<U>{
+        Vec3::new(U::from_number(self.x), U::from_number(self.y), U::from_number(self.z))
+    }
+    /// Convert to a float Vector
+    pub fn to_vector(self) -> Vector3{
+        Vector3::new(self.x.to_f32(), self.y.to_f32(), self.z.to_f32())
+    }
+    /// Returns the length of `self` squared
+    ///
+    /// Cheaper than `magnitude`, good enough for comparing lengths
+    pub fn magnitude_squared(self) -> T{
+        self.dot(self)
+    }
+    /// Returns the smaller value of each axis
+    pub fn min(self, other: Self) -> Self{
+        Self::new(lesser(self.x, other.x), lesser(self.y, other.y), lesser(self.z, other.z))
+    }
+    /// Returns the bigger value of each axis
+    pub fn max(self, other: Self) -> Self{
+        Self::new(greater(self.x, other.x), greater(self.y, other.y), greater(self.z, other.z))
+    }
+    /// Returns `self` with each axis kept between `low` and `high`
+    pub fn clamp(self, low: Self, high: Self) -> Self{
+        self.max(low).min(high)
+    }
+}
+impl<T: Signed> Vec3<T>{
+    /// Returns the absolute value of each axis
+    pub fn abs(self) -> Self{
+        Self::new(self.x.abs(), self.y.abs(), self.z.abs())
     }
     /// Returns the cross product Vector of `self` and `other`
-    /// 
+    ///
     /// Simpler explanation: Returns a Vector perpendicular to `self` and `other` with the magnitude of it being *inverse* of dot product: it defines how *different* the two Vectors are
     pub fn cross(self, other: Self) -> Self{
         Self{
@@ -198,36 +438,127 @@ impl Vector3{
             z: self.x * other.y - self.y * other.x,
         }
     }
+}
+impl<T: Float> Vec3<T>{
+    /// Returns a point `t` of the way from `self` to `other` along a straight line
+    ///
+    /// `t` of 0 gives `self`, 1 gives `other`, values outside of that extrapolate
+    pub fn lerp(self, other: Self, t: T) -> Self{
+        self + (other - self) * t
+    }
+    /// Returns a Vector `t` of the way from `self` to `other` along an arc
+    ///
+    /// Direction turns at a constant rate, magnitude changes linearly.
+    /// Falls back to `lerp` when the Vectors point the same or opposite way, as the arc isn't defined then
+    pub fn slerp(self, other: Self, t: T) -> Self{
+        let (from, to) = (self.magnitude(), other.magnitude());
+        if from == T::ZERO || to == T::ZERO{
+            return self.lerp(other, t)
+        }
+        let (a, b) = (self / from, other / to);
+        // Both are unit length, so these are the sine and cosine of the angle between them
+        let (sin, cos) = (a.cross(b).magnitude(), a.dot(b));
+        if sin.to_f32() < 1e-5{
+            return self.lerp(other, t)
+        }
+        let angle = sin.atan2(cos);
+        let direction = (a * ((T::ONE - t) * angle).sin_cos().0 + b * (t * angle).sin_cos().0) / sin;
+        direction * (from + (to - from) * t)
+    }
+    /// Returns a Vector that's a projection of `self` onto `other`
+    ///
+    /// Simpler explanation: It flattens `self` onto `other`
+    ///
+    /// Projecting onto a zero Vector gives a zero Vector
+    pub fn project(self, other: Self) -> Self{
+        let length = other.magnitude_squared();
+        if length == T::ZERO{
+            return Self::default()
+        }
+        other * (self.dot(other) / length)
+    }
     /// Returns a reflection of `self` around `other`
     pub fn reflect(self, other: Self) -> Self{
-        self.project(other) * 2.0 - self
+        self.project(other) * (T::ONE + T::ONE) - self
     }
     /// Returns distance between endpoint of `self` and `other`
-    pub fn distance(self, other: Self) -> f32{
+    pub fn distance(self, other: Self) -> T{
         (self - other).magnitude()
     }
     /// Returns self normalized to magnitude of 1
+    ///
+    /// ## WARNING
+    /// A zero Vector has no direction and gives NaN, use `try_normalize` or `normalize_or_zero` if it can be zero
     pub fn normalize(self) -> Self{
         self / self.magnitude()
     }
+    /// Returns self normalized to magnitude of 1, or `None` if it's a zero Vector or not finite
+    pub fn try_normalize(self) -> Option<Self>{
+        let magnitude = self.magnitude();
+        // NaN fails the comparison too, an infinite magnitude would turn the axes into NaN
+        match magnitude > T::ZERO && magnitude.is_finite(){
+            true => Some(self / magnitude),
+            false => None,
+        }
+    }
+    /// Returns self normalized to magnitude of 1, or a zero Vector if it's a zero Vector
+    pub fn normalize_or_zero(self) -> Self{
+        self.try_normalize().unwrap_or_default()
+    }
     /// Returns the angle between `self` and `other` in Radians
-    pub fn angle_between(self, other: Self) -> f32{
-        (self.dot(other) / (self.magnitude() * other.magnitude())).acos()
+    ///
+    /// Zero Vectors have no direction, so it's 0 if either of them is one
+    pub fn angle_between(self, other: Self) -> T{
+        let magnitude = self.magnitude() * other.magnitude();
+        if magnitude == T::ZERO{
+            return T::ZERO
+        }
+        // Rounding can push the cosine just past 1, where `acos` is NaN
+        let cos = self.dot(other) / magnitude;
+        greater(lesser(cos, T::ONE), -T::ONE).acos()
+    }
+    /// Returns `true` if every axis of `self` is within `epsilon` of `other`
+    pub fn approx_eq(self, other: Self, epsilon: T) -> bool{
+        let diff = (self - other).abs();
+        diff.x <= epsilon && diff.y <= epsilon && diff.z <= epsilon
     }
     /// Returns the length of `self`
-    /// 
-    /// ## WARNING
-    /// Because this uses Pythagorean Theorem to calculate, a Vector too big may cause an overflow, and subsequently a crash
-    pub fn magnitude(self) -> f32{
-        (self.x.powi(2) + self.y.powi(2) + self.x.powi(2)).sqrt()
+    ///
+    /// Uses `hypot`, so big Vectors don't overflow halfway through
+    pub fn magnitude(self) -> T{
+        self.x.hypot(self.y).hypot(self.z)
+    }
+}
+impl<T: Integer> Vec3<T>{
+    /// Returns the grid distance when moving only along the axes
+    ///
+    /// Distances too big for `T::Unsigned` stop at it's maximum
+    pub fn manhattan(self, other: Self) -> T::Unsigned{
+        self.x.abs_diff(other.x)
+            .saturating_add(self.y.abs_diff(other.y))
+            .saturating_add(self.z.abs_diff(other.z))
+    }
+    /// Returns the grid distance when diagonal moves are allowed
+    pub fn chebyshev(self, other: Self) -> T::Unsigned{
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y)).max(self.z.abs_diff(other.z))
+    }
+    /// Convert a float Vector using the given rounding mode
+    ///
+    /// Values out of range are clamped to the type's bounds
+    pub fn from_vector(vector: Vector3, rounding: Rounding) -> Self{
+        Self::new(
+            T::from_f32(rounding.apply(vector.x)),
+            T::from_f32(rounding.apply(vector.y)),
+            T::from_f32(rounding.apply(vector.z))
+        )
     }
-}   
-impl std::fmt::Display for Vector3{
+}
+impl<T: Number + std::fmt::Display> std::fmt::Display for Vec3<T>{
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!("({}, {}, {})", self.x, self.y, self.z))
     }
 }
-impl Add for Vector3{
+impl<T: Number> Add for Vec3<T>{
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -238,14 +569,14 @@ impl Add for Vector3{
         }
     }
 }
-impl AddAssign for Vector3{
+impl<T: Number> AddAssign for Vec3<T>{
     fn add_assign(&mut self, rhs: Self) {
         self.x += rhs.x;
         self.y += rhs.y;
         self.z += rhs.z
     }
 }
-impl Sub for Vector3{
+impl<T: Number> Sub for Vec3<T>{
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
@@ -256,17 +587,17 @@ impl Sub for Vector3{
         }
     }
 }
-impl SubAssign for Vector3{
+impl<T: Number> SubAssign for Vec3<T>{
     fn sub_assign(&mut self, rhs: Self) {
         self.x -= rhs.x;
         self.y -= rhs.y;
         self.z -= rhs.z
     }
 }
-impl Mul<f32> for Vector3{
+impl<T: Number> Mul<T> for Vec3<T>{
     type Output = Self;
 
-    fn mul(self, rhs: f32) -> Self::Output {
+    fn mul(self, rhs: T) -> Self::Output {
         Self{
             x: self.x * rhs,
             y: self.y * rhs,
@@ -274,17 +605,17 @@ impl Mul<f32> for Vector3{
         }
     }
 }
-impl MulAssign<f32> for Vector3{
-    fn mul_assign(&mut self, rhs: f32) {
+impl<T: Number> MulAssign<T> for Vec3<T>{
+    fn mul_assign(&mut self, rhs: T) {
         self.x *= rhs;
         self.y *= rhs;
         self.z *= rhs
     }
 }
-impl Mul<Vector3> for Vector3{
+impl<T: Number> Mul<Vec3<T>> for Vec3<T>{
     type Output = Self;
 
-    fn mul(self, rhs: Vector3) -> Self::Output {
+    fn mul(self, rhs: Vec3<T>) -> Self::Output {
         Self{
             x: self.x * rhs.x,
             y: self.y * rhs.y,
@@ -292,17 +623,17 @@ impl Mul<Vector3> for Vector3{
         }
     }
 }
-impl MulAssign<Vector3> for Vector3{
-    fn mul_assign(&mut self, rhs: Vector3) {
+impl<T: Number> MulAssign<Vec3<T>> for Vec3<T>{
+    fn mul_assign(&mut self, rhs: Vec3<T>) {
         self.x *= rhs.x;
         self.y *= rhs.y;
         self.z *= rhs.z;
     }
 }
-impl Div<f32> for Vector3{
+impl<T: Number> Div<T> for Vec3<T>{
     type Output = Self;
 
-    fn div(self, rhs: f32) -> Self::Output {
+    fn div(self, rhs: T) -> Self::Output {
         Self{
             x: self.x / rhs,
             y: self.y / rhs,
@@ -310,17 +641,17 @@ impl Div<f32> for Vector3{
         }
     }
 }
-impl DivAssign<f32> for Vector3 {
-    fn div_assign(&mut self, rhs: f32) {
+impl<T: Number> DivAssign<T> for Vec3<T> {
+    fn div_assign(&mut self, rhs: T) {
         self.x /= rhs;
         self.y /= rhs;
         self.z /= rhs
     }
 }
-impl Div<Vector3> for Vector3{
+impl<T: Number> Div<Vec3<T>> for Vec3<T>{
     type Output = Self;
 
-    fn div(self, rhs: Vector3) -> Self::Output {
+    fn div(self, rhs: Vec3<T>) -> Self::Output {
         Self{
             x: self.x / rhs.x,
             y: self.y / rhs.y,
@@ -328,10 +659,90 @@ impl Div<Vector3> for Vector3{
         }
     }
 }
-impl DivAssign<Vector3> for Vector3{
-    fn div_assign(&mut self, rhs: Vector3) {
+impl<T: Number> DivAssign<Vec3<T>> for Vec3<T>{
+    fn div_assign(&mut self, rhs: Vec3<T>) {
         self.x /= rhs.x;
         self.y /= rhs.y;
         self.z /= rhs.z;
     }
-}
\ No newline at end of file
+}
+
+impl<T: Signed> Neg for Vec3<T>{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+impl<T> Index<usize> for Vec3<T>{
+    type Output = T;
+
+    /// Axis by index, `0` is X, `1` is Y and `2` is Z
+    fn index(&self, index: usize) -> &Self::Output {
+        match index{
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Vec3 index out of bounds: {index}")
+        }
+    }
+}
+impl<T> IndexMut<usize> for Vec3<T>{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match index{
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("Vec3 index out of bounds: {index}")
+        }
+    }
+}
+
+impl From<Vector2i> for Vector2{
+    fn from(value: Vector2i) -> Self {
+        value.to_vector()
+    }
+}
+impl From<Vector2u> for Vector2{
+    fn from(value: Vector2u) -> Self {
+        value.to_vector()
+    }
+}
+impl From<Vector3i> for Vector3{
+    fn from(value: Vector3i) -> Self {
+        value.to_vector()
+    }
+}
+impl From<Vector3u> for Vector3{
+    fn from(value: Vector3u) -> Self {
+        value.to_vector()
+    }
+}
+impl TryFrom<Vector2u> for Vector2i{
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(value: Vector2u) -> Result<Self, Self::Error> {
+        Ok(Self::new(value.x.try_into()?, value.y.try_into()?))
+    }
+}
+impl TryFrom<Vector2i> for Vector2u{
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(value: Vector2i) -> Result<Self, Self::Error> {
+        Ok(Self::new(value.x.try_into()?, value.y.try_into()?))
+    }
+}
+impl TryFrom<Vector3u> for Vector3i{
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(value: Vector3u) -> Result<Self, Self::Error> {
+        Ok(Self::new(value.x.try_into()?, value.y.try_into()?, value.z.try_into()?))
+    }
+}
+impl TryFrom<Vector3i> for Vector3u{
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(value: Vector3i) -> Result<Self, Self::Error> {
+        Ok(Self::new(value.x.try_into()?, value.y.try_into()?, value.z.try_into()?))
+    }
+}
@@ -0,0 +1,75 @@
+//! Helpers for the checks that need a running World
+//!
+//! Everything that depends on how the engine sets up a World, runs Systems and reads Events is kept here
+
+#![allow(dead_code)]
+
+use manufacture_core::comp::{CMDSprite, PlayerController, Tags, Transform2D};
+use manufacture_core::types::{EntityPrefab, Vector2};
+use manufacture_engine::ECS::dispatcher::DispatcherBuilder;
+use manufacture_engine::ECS::entity::EntityBuilder;
+use manufacture_engine::ECS::request::SystemData;
+use manufacture_engine::prelude::*;
+
+/// Create a World with the Core library initiated
+pub fn world() -> World{
+    let mut world = World::new();
+    manufacture_core::init(&mut world, &mut DispatcherBuilder::new());
+    world
+}
+
+/// Run a Command right away
+pub fn run<C: Command>(world: &mut World, mut command: C){
+    command.execute(world);
+}
+
+/// Run a single frame of a freshly made System, followed by the Commands it sent
+pub fn run_system<S: System>(world: &mut World){
+    step(world, &mut S::new());
+}
+
+/// Run a single frame of a System that keeps it's state between frames, followed by the Commands it sent
+pub fn step<S: System>(world: &mut World, system: &mut S){
+    {
+        let data = <S::Data<'_> as SystemData>::fetch(world);
+        system.execute(Request::new(data));
+    }
+    world.run_commands();
+}
+
+/// Collect IDs of Entities a Query goes through
+pub fn query<D: QueryData, F: QueryFilter>(world: &World) -> Vec<usize>{
+    <Query<'_, D, F> as SystemData>::fetch(world).iter().map(|(id, _)| id).collect()
+}
+
+/// Collect every Event of a type sent so far
+pub fn events<E: Event, T>(world: &World, map: impl Fn(&E) -> T) -> Vec<T>{
+    world.get_event_reader::<E>().read().map(map).collect()
+}
+
+/// A plain Entity with a few core Components
+pub struct Orc{
+    pub loc: Vector2
+}
+impl EntityPrefab for Orc{
+    const ID: &'static str = "Orc";
+    fn spawn(&self, builder: EntityBuilder<'_>) {
+        let mut tags = Tags::new();
+        tags.tag_id("Enemy");
+        builder
+            .with(Transform2D{loc: self.loc, rot: 0.0, scale: Vector2::new(1.0, 1.0)})
+            .with(tags)
+            .with(CMDSprite{size_x: 1, size_y: 1, z_index: 0, data: vec![('o', (0, 255, 0), (0, 0, 0))]});
+    }
+}
+
+/// An Entity the player controls
+pub struct Hero;
+impl EntityPrefab for Hero{
+    const ID: &'static str = "Hero";
+    fn spawn(&self, builder: EntityBuilder<'_>) {
+        builder
+            .with(Transform2D{loc: Vector2::new(0.0, 0.0), rot: 0.0, scale: Vector2::new(1.0, 1.0)})
+            .with(PlayerController{pid: 1, active: true});
+    }
+}
@@ -0,0 +1,123 @@
+//! Checks that failing Commands report why through `CommandFailed`
+
+mod common;
+
+use common::*;
+use manufacture_core::commands::*;
+use manufacture_core::comp::{TagId, Tags, Transform2D};
+use manufacture_core::events::*;
+use manufacture_core::resources::SceneRegistry;
+use manufacture_core::types::*;
+use manufacture_engine::prelude::*;
+
+fn failures(world: &World) -> Vec<(&'static str, CommandError)>{
+    events::<CommandFailed, _>(world, |event| (event.0, event.1.clone()))
+}
+
+fn name<C>() -> &'static str{
+    std::any::type_name::<C>()
+}
+
+/// Spawns an Entity and despawns it again, returning it's stale Token
+fn stale(world: &mut World) -> Token{
+    run(world, Spawn);
+    let token = *events::<EntitySpawned, _>(world, |event| event.0).last().unwrap();
+    run(world, DespawnToken(token));
+    token
+}
+
+#[test]
+fn stale_tokens(){
+    let mut world = world();
+    let token = stale(&mut world);
+
+    run(&mut world, DespawnToken(token));
+    run(&mut world, CloneEntity(token));
+    run(&mut world, InsertComponent::new(token, Tags::new()));
+    run(&mut world, RemoveComponent::<Tags>::new(token));
+    run(&mut world, AddTag(token, TagId::new("Enemy")));
+    run(&mut world, RemoveTag(token, TagId::new("Enemy")));
+    run(&mut world, DetachChild(token));
+    run(&mut world, DespawnRecursive(token));
+
+    assert_eq!(failures(&world), vec![
+        (name::<DespawnToken>(), CommandError::StaleToken(token)),
+        (name::<CloneEntity>(), CommandError::StaleToken(token)),
+        (name::<InsertComponent<Tags>>(), CommandError::StaleToken(token)),
+        (name::<RemoveComponent<Tags>>(), CommandError::StaleToken(token)),
+        (name::<AddTag>(), CommandError::StaleToken(token)),
+        (name::<RemoveTag>(), CommandError::StaleToken(token)),
+        (name::<DetachChild>(), CommandError::StaleToken(token)),
+        (name::<DespawnRecursive>(), CommandError::StaleToken(token)),
+    ]);
+}
+
+#[test]
+fn stale_ids(){
+    let mut world = world();
+    let token = stale(&mut world);
+
+    run(&mut world, DespawnID(token.id()));
+    assert_eq!(failures(&world), vec![(name::<DespawnID>(), CommandError::StaleID(token.id()))]);
+}
+
+#[test]
+fn missing_components(){
+    let mut world = world();
+    run(&mut world, Spawn);
+    let token = events::<EntitySpawned, _>(&world, |event| event.0)[0];
+
+    run(&mut world, RemoveComponent::<Transform2D>::new(token));
+    assert_eq!(failures(&world), vec![
+        (name::<RemoveComponent<Transform2D>>(), CommandError::MissingComponent(token, Transform2D::ID)),
+    ]);
+}
+
+#[test]
+fn missing_prefabs(){
+    let mut world = world();
+    run(&mut world, SpawnPrefabById::new("Dragon"));
+
+    assert!(events::<EntitySpawned, _>(&world, |event| event.0).is_empty());
+    assert_eq!(failures(&world), vec![(name::<SpawnPrefabById>(), CommandError::MissingPrefab("Dragon".to_string()))]);
+}
+
+#[test]
+fn hierarchy_cycles(){
+    let mut world = world();
+    run(&mut world, Spawn);
+    let token = events::<EntitySpawned, _>(&world, |event| event.0)[0];
+
+    run(&mut world, AttachChild{parent: token, child: token});
+    assert_eq!(failures(&world), vec![
+        (name::<AttachChild>(), CommandError::HierarchyCycle{parent: token, child: token}),
+    ]);
+}
+
+#[test]
+fn unresolved_handles(){
+    let mut world = world();
+    let handle = SpawnHandle::new();
+    run(&mut world, AfterSpawn::new(&handle, DespawnToken));
+
+    let failures = failures(&world);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].1, CommandError::UnresolvedHandle);
+}
+
+#[test]
+fn scenes(){
+    let mut world = world();
+    world.fetch_res_mut::<SceneRegistry>().register(Scene::new("Level"));
+
+    run(&mut world, LoadScene::new("Cave"));
+    run(&mut world, SwitchScene::new("Cave"));
+    run(&mut world, LoadScene::new("Level"));
+    run(&mut world, LoadScene::new("Level"));
+
+    assert_eq!(failures(&world), vec![
+        (name::<LoadScene>(), CommandError::MissingScene("Cave".to_string())),
+        (name::<SwitchScene>(), CommandError::MissingScene("Cave".to_string())),
+        (name::<LoadScene>(), CommandError::SceneAlreadyLoaded("Level".to_string())),
+    ]);
+}
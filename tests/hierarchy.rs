@@ -0,0 +1,118 @@
+//! Checks for parent-child links between Entities
+
+mod common;
+
+use common::*;
+use manufacture_core::commands::*;
+use manufacture_core::comp::{Children, Parent};
+use manufacture_core::events::*;
+use manufacture_core::types::*;
+use manufacture_engine::prelude::*;
+
+fn spawn_many(world: &mut World, count: usize) -> Vec<Token>{
+    for _ in 0..count{
+        run(world, Spawn);
+    }
+    events::<EntitySpawned, _>(world, |event| event.0)
+}
+
+fn parent_of(world: &World, child: Token) -> Option<Token>{
+    world.fetch::<Parent>().get(&child.id()).map(|parent| parent.0)
+}
+
+fn children_of(world: &World, parent: Token) -> Vec<Token>{
+    world.fetch::<Children>().get(&parent.id()).map(|children| children.iter().copied().collect()).unwrap_or_default()
+}
+
+fn failures(world: &World) -> Vec<(&'static str, CommandError)>{
+    events::<CommandFailed, _>(world, |event| (event.0, event.1.clone()))
+}
+
+#[test]
+fn cycles_are_rejected(){
+    let mut world = world();
+    let tokens = spawn_many(&mut world, 3);
+    let [a, b, c] = [tokens[0], tokens[1], tokens[2]];
+    run(&mut world, AttachChild{parent: a, child: b});
+    run(&mut world, AttachChild{parent: b, child: c});
+
+    run(&mut world, AttachChild{parent: c, child: a});
+    run(&mut world, AttachChild{parent: a, child: a});
+    let errors = failures(&world).into_iter().map(|(_, error)| error).collect::<Vec<_>>();
+    assert_eq!(errors, vec![
+        CommandError::HierarchyCycle{parent: c, child: a},
+        CommandError::HierarchyCycle{parent: a, child: a}
+    ]);
+
+    // The hierarchy is left untouched
+    assert_eq!((parent_of(&world, a), parent_of(&world, b), parent_of(&world, c)), (None, Some(a), Some(b)));
+    assert_eq!((children_of(&world, a), children_of(&world, b), children_of(&world, c)), (vec![b], vec![c], vec![]));
+}
+
+#[test]
+fn reparenting(){
+    let mut world = world();
+    let tokens = spawn_many(&mut world, 3);
+    let [a, b, child] = [tokens[0], tokens[1], tokens[2]];
+    run(&mut world, AttachChild{parent: a, child});
+    run(&mut world, AttachChild{parent: b, child});
+
+    assert_eq!(parent_of(&world, child), Some(b));
+    assert_eq!((children_of(&world, a), children_of(&world, b)), (vec![], vec![child]));
+    // `a` lost it's only child, so it lost it's `Children` Component too
+    assert!(world.fetch::<Children>().get(&a.id()).is_none());
+    assert_eq!(events::<ComponentAdded<Parent>, _>(&world, |event| event.0), vec![child, child]);
+    assert_eq!(events::<ComponentRemoved<Parent>, _>(&world, |event| event.0), vec![child]);
+    assert_eq!(events::<ComponentAdded<Children>, _>(&world, |event| event.0), vec![a, b]);
+    assert_eq!(events::<ComponentRemoved<Children>, _>(&world, |event| event.0), vec![a]);
+
+    run(&mut world, DetachChild(child));
+    assert_eq!(parent_of(&world, child), None);
+    assert!(world.fetch::<Children>().get(&b.id()).is_none());
+
+    // Detaching a root does nothing
+    run(&mut world, DetachChild(child));
+    assert!(failures(&world).is_empty());
+}
+
+#[test]
+fn despawning_unlinks(){
+    let mut world = world();
+    let tokens = spawn_many(&mut world, 3);
+    let [parent, first, second] = [tokens[0], tokens[1], tokens[2]];
+    run(&mut world, AttachChild{parent, child: first});
+    run(&mut world, AttachChild{parent, child: second});
+
+    run(&mut world, DespawnToken(first));
+    assert_eq!(children_of(&world, parent), vec![second]);
+
+    // Children of a despawned parent become roots
+    run(&mut world, DespawnToken(parent));
+    assert!(world.validate_token(&second));
+    assert_eq!(parent_of(&world, second), None);
+}
+
+#[test]
+fn recursive_despawn(){
+    let mut world = world();
+    let tokens = spawn_many(&mut world, 5);
+    let [root, a, b, a1, other] = [tokens[0], tokens[1], tokens[2], tokens[3], tokens[4]];
+    run(&mut world, AttachChild{parent: root, child: a});
+    run(&mut world, AttachChild{parent: root, child: b});
+    run(&mut world, AttachChild{parent: a, child: a1});
+    run(&mut world, AttachChild{parent: other, child: root});
+
+    run(&mut world, DespawnRecursive(root));
+    for token in [root, a, b, a1]{
+        assert!(!world.validate_token(&token));
+    }
+    // Children go first, the rest of the World stays
+    let despawned = events::<EntityDespawned, _>(&world, |event| event.0);
+    assert_eq!(despawned, vec![a1, b, a, root]);
+    assert!(world.validate_token(&other));
+    assert!(world.fetch::<Children>().get(&other.id()).is_none());
+    assert!(failures(&world).is_empty());
+
+    run(&mut world, DespawnRecursive(root));
+    assert_eq!(failures(&world), vec![(std::any::type_name::<DespawnRecursive>(), CommandError::StaleToken(root))]);
+}
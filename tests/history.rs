@@ -0,0 +1,206 @@
+//! Checks for undoing and redoing Commands through `CommandHistory`
+
+mod common;
+
+use common::*;
+use manufacture_core::commands::*;
+use manufacture_core::comp::{Children, Parent, PlayerController, Tags, Transform2D};
+use manufacture_core::events::*;
+use manufacture_core::resources::CommandHistory;
+use manufacture_core::types::*;
+use manufacture_engine::prelude::*;
+
+fn spawned(world: &World) -> Vec<Token>{
+    events::<EntitySpawned, _>(world, |event| event.0)
+}
+
+fn last_spawned(world: &World) -> Token{
+    *spawned(world).last().unwrap()
+}
+
+fn loc(world: &World, token: Token) -> Option<Vector2>{
+    world.fetch::<Transform2D>().get(&token.id()).map(|transform| transform.loc)
+}
+
+fn transform(x: f32) -> Transform2D{
+    Transform2D{loc: Vector2::new(x, 0.0), rot: 0.0, scale: Vector2::new(1.0, 1.0)}
+}
+
+#[test]
+fn spawn(){
+    let mut world = world();
+    run(&mut world, Record(Spawn));
+    let token = last_spawned(&world);
+
+    run(&mut world, Undo);
+    assert!(!world.validate_token(&token));
+    assert!(!world.fetch_res::<CommandHistory>().can_undo());
+
+    run(&mut world, Redo);
+    let respawned = last_spawned(&world);
+    assert_ne!(respawned, token);
+    assert!(world.validate_token(&respawned));
+
+    run(&mut world, Undo);
+    assert!(!world.validate_token(&respawned));
+}
+
+#[test]
+fn despawn(){
+    let mut world = world();
+    run(&mut world, SpawnPrefab(Hero));
+    let hero = last_spawned(&world);
+    run(&mut world, InsertComponent::new(hero, Tags::new()));
+
+    run(&mut world, Record(DespawnToken(hero)));
+    assert!(!world.validate_token(&hero));
+
+    run(&mut world, Undo);
+    let respawned = last_spawned(&world);
+    assert!(world.validate_token(&respawned));
+    assert!(loc(&world, respawned).is_some());
+    assert!(world.fetch::<Tags>().get(&respawned.id()).is_some());
+    // Unlike clones, the respawned Entity is still controlled by the player
+    assert_eq!(world.fetch::<PlayerController>().get(&respawned.id()).map(|controller| controller.pid), Some(1));
+
+    run(&mut world, Redo);
+    assert!(!world.validate_token(&respawned));
+    assert_eq!(events::<EntityDespawned, _>(&world, |event| event.0), vec![hero, respawned]);
+}
+
+#[test]
+fn despawn_restores_hierarchy(){
+    let mut world = world();
+    for _ in 0..4{
+        run(&mut world, Spawn);
+    }
+    let tokens = spawned(&world);
+    let [root, middle, leaf, other] = [tokens[0], tokens[1], tokens[2], tokens[3]];
+    run(&mut world, AttachChild{parent: root, child: middle});
+    run(&mut world, AttachChild{parent: middle, child: leaf});
+    run(&mut world, AttachChild{parent: middle, child: other});
+
+    run(&mut world, Record(DespawnToken(middle)));
+    assert!(world.fetch::<Children>().get(&root.id()).is_none());
+    // Children that got a new parent in the meantime stay where they are
+    run(&mut world, AttachChild{parent: root, child: other});
+
+    run(&mut world, Undo);
+    let restored = last_spawned(&world);
+    assert_eq!(world.fetch::<Parent>().get(&restored.id()).map(|parent| parent.0), Some(root));
+    assert_eq!(world.fetch::<Parent>().get(&leaf.id()).map(|parent| parent.0), Some(restored));
+    assert_eq!(world.fetch::<Parent>().get(&other.id()).map(|parent| parent.0), Some(root));
+    assert_eq!(world.fetch::<Children>().get(&restored.id()).map(|children| children.len()), Some(1));
+    assert!(events::<CommandFailed, _>(&world, |event| event.0).is_empty());
+
+    // Redoing and undoing again follows the respawned Entity
+    run(&mut world, Redo);
+    assert!(world.fetch::<Parent>().get(&leaf.id()).is_none());
+    run(&mut world, Undo);
+    let again = last_spawned(&world);
+    assert_eq!(world.fetch::<Parent>().get(&leaf.id()).map(|parent| parent.0), Some(again));
+}
+
+#[test]
+fn insert_component(){
+    let mut world = world();
+    run(&mut world, Spawn);
+    let token = last_spawned(&world);
+
+    run(&mut world, Record(InsertComponent::new(token, transform(1.0))));
+    run(&mut world, Record(InsertComponent::new(token, transform(2.0))));
+    assert_eq!(loc(&world, token), Some(Vector2::new(2.0, 0.0)));
+
+    // Replacing puts the old Component back, adding removes it
+    run(&mut world, Undo);
+    assert_eq!(loc(&world, token), Some(Vector2::new(1.0, 0.0)));
+    run(&mut world, Undo);
+    assert_eq!(loc(&world, token), None);
+
+    run(&mut world, Redo);
+    run(&mut world, Redo);
+    assert_eq!(loc(&world, token), Some(Vector2::new(2.0, 0.0)));
+    assert!(!world.fetch_res::<CommandHistory>().can_redo());
+}
+
+#[test]
+fn remove_component(){
+    let mut world = world();
+    run(&mut world, Spawn);
+    let token = last_spawned(&world);
+    run(&mut world, InsertComponent::new(token, transform(3.0)));
+
+    run(&mut world, Record(RemoveComponent::<Transform2D>::new(token)));
+    assert_eq!(loc(&world, token), None);
+
+    run(&mut world, Undo);
+    assert_eq!(loc(&world, token), Some(Vector2::new(3.0, 0.0)));
+    run(&mut world, Redo);
+    assert_eq!(loc(&world, token), None);
+
+    // Nothing to remove means nothing to undo
+    run(&mut world, Record(RemoveComponent::<Transform2D>::new(token)));
+    run(&mut world, Undo);
+    assert_eq!(loc(&world, token), Some(Vector2::new(3.0, 0.0)));
+}
+
+#[test]
+fn component_changes_survive_respawn(){
+    let mut world = world();
+    run(&mut world, Spawn);
+    let token = last_spawned(&world);
+
+    run(&mut world, Record(InsertComponent::new(token, transform(1.0))));
+    run(&mut world, Record(DespawnToken(token)));
+    run(&mut world, Undo);
+    let respawned = last_spawned(&world);
+    assert_eq!(loc(&world, respawned), Some(Vector2::new(1.0, 0.0)));
+
+    // The older step acts on the respawned Entity
+    run(&mut world, Undo);
+    assert_eq!(loc(&world, respawned), None);
+}
+
+#[test]
+fn transactions(){
+    let mut world = world();
+    run(&mut world, BeginTransaction("Spawn two".to_string()));
+    run(&mut world, Record(Spawn));
+    run(&mut world, Record(Spawn));
+    run(&mut world, EndTransaction);
+    let tokens = spawned(&world);
+    assert_eq!(world.fetch_res::<CommandHistory>().undo_label(), Some("Spawn two"));
+
+    run(&mut world, Undo);
+    assert!(tokens.iter().all(|token| !world.validate_token(token)));
+    assert_eq!(world.fetch_res::<CommandHistory>().redo_label(), Some("Spawn two"));
+
+    run(&mut world, Redo);
+    assert_eq!(spawned(&world).len(), 4);
+
+    // A new step drops what could be redone
+    run(&mut world, Undo);
+    run(&mut world, Record(Spawn));
+    assert!(!world.fetch_res::<CommandHistory>().can_redo());
+}
+
+#[test]
+fn max_depth(){
+    let mut world = world();
+    world.fetch_res_mut::<CommandHistory>().set_max_depth(2);
+    for _ in 0..3{
+        run(&mut world, Record(Spawn));
+    }
+    let tokens = spawned(&world);
+
+    run(&mut world, Undo);
+    run(&mut world, Undo);
+    run(&mut world, Undo);
+    assert!(world.validate_token(&tokens[0]));
+    assert!(!world.validate_token(&tokens[1]));
+    assert!(!world.validate_token(&tokens[2]));
+
+    world.fetch_res_mut::<CommandHistory>().clear();
+    run(&mut world, Redo);
+    assert_eq!(spawned(&world).len(), 3);
+}
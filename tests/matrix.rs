@@ -0,0 +1,220 @@
+//! Checks for the matrix types and the `Transform2D`/`Transform3D` helpers
+
+use manufacture_core::comp::{Transform2D, Transform3D};
+use manufacture_core::types::*;
+
+const RUNS: usize = 1024;
+
+/// Tiny xorshift generator, same as in the Vector checks
+struct Rng(u64);
+impl Rng{
+    fn new() -> Self{
+        Self(0x2545_F491_4F6C_DD1D)
+    }
+    fn next(&mut self) -> u64{
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+    fn float(&mut self, range: f32) -> f32{
+        ((self.next() % 2_000_001) as f32 / 1_000_000.0 - 1.0) * range
+    }
+    fn vec2(&mut self, range: f32) -> Vector2{
+        Vector2::new(self.float(range), self.float(range))
+    }
+    fn transform2d(&mut self) -> Transform2D{
+        Transform2D{
+            loc: self.vec2(50.0),
+            rot: self.float(3.0),
+            scale: self.vec2(2.0) + Vector2::new(3.0, 3.0),
+        }
+    }
+    fn vec3(&mut self, range: f32) -> Vector3{
+        Vector3::new(self.float(range), self.float(range), self.float(range))
+    }
+}
+
+fn close(a: Vector3, b: Vector3) -> bool{
+    (a - b).magnitude() <= 1e-3 * a.magnitude().max(1.0)
+}
+
+fn close2(a: Vector2, b: Vector2) -> bool{
+    (a - b).magnitude() <= 1e-3 * a.magnitude().max(1.0)
+}
+
+#[test]
+fn euler_order_is_x_then_y_then_z(){
+    let rot = Vector3::new(0.3, -0.7, 1.1);
+    let x = Mat3::from_euler(Vector3::new(rot.x, 0.0, 0.0));
+    let y = Mat3::from_euler(Vector3::new(0.0, rot.y, 0.0));
+    let z = Mat3::from_euler(Vector3::new(0.0, 0.0, rot.z));
+    let v = Vector3::new(1.0, 2.0, 3.0);
+    assert!(close(Mat3::from_euler(rot) * v, z * (y * (x * v))));
+
+    // Z is up, so heading turns X towards Y
+    let heading = Mat3::from_euler(Vector3::new(0.0, 0.0, std::f32::consts::FRAC_PI_2));
+    assert!(close(heading * Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)));
+}
+
+#[test]
+fn quat_matches_matrix(){
+    let mut rng = Rng::new();
+    for _ in 0..RUNS{
+        let rot = rng.vec3(3.0);
+        let v = rng.vec3(10.0);
+        let quat = Quat::from_euler(rot);
+        let mat = Mat3::from_euler(rot);
+        assert!(close(quat * v, mat * v));
+        assert!(close(Quat::from_mat3(mat) * v, mat * v));
+        assert!(close(Mat3::from_euler(quat.to_euler()) * v, mat * v));
+        assert!(close(quat.inverse() * (quat * v), v));
+    }
+}
+
+#[test]
+fn matrix_inverse(){
+    let mut rng = Rng::new();
+    for _ in 0..RUNS{
+        let linear = Mat3::from_euler(rng.vec3(3.0)) * Mat3::from_scale(rng.vec3(2.0) + Vector3::new(3.0, 3.0, 3.0));
+        let mat = Mat4::from_parts(linear, rng.vec3(50.0));
+        let v = rng.vec3(10.0);
+        let inv = mat.inverse().unwrap();
+        assert!(close(inv.transform_point(mat.transform_point(v)), v));
+        assert!(close(linear.inverse().unwrap() * (linear * v), v));
+    }
+    assert!(Mat3::from_scale(Vector3::new(1.0, 0.0, 1.0)).inverse().is_none());
+    assert!(Mat4::from_mat3(Mat3::from_scale(Vector3::new(1.0, 1.0, 0.0))).inverse().is_none());
+}
+
+#[test]
+fn transform_round_trip(){
+    let mut rng = Rng::new();
+    for _ in 0..RUNS{
+        let uniform = rng.float(1.0) + 2.0;
+        let transform = Transform3D{
+            loc: rng.vec3(50.0),
+            rot: rng.vec3(3.0),
+            scale: Vector3::new(uniform, uniform, uniform),
+        };
+        let v = rng.vec3(10.0);
+        let moved = transform.transform_point(v);
+        assert!(close(transform.to_matrix().transform_point(v), moved));
+        assert!(close(transform.inverse_transform_point(moved), v));
+        assert!(close(transform.inverse().transform_point(moved), v));
+    }
+}
+
+#[test]
+fn look_at_faces_target(){
+    let mut rng = Rng::new();
+    let up = Vector3::new(0.0, 0.0, 1.0);
+    for _ in 0..RUNS{
+        let mut transform = Transform3D{
+            loc: rng.vec3(50.0),
+            rot: rng.vec3(3.0),
+            scale: Vector3::new(1.0, 1.0, 1.0),
+        };
+        let target = rng.vec3(50.0);
+        transform.look_at(target, up);
+
+        let forward = transform.rotation_matrix() * Vector3::new(0.0, 1.0, 0.0);
+        assert!(close(forward, (target - transform.loc).normalize()));
+        // No roll, right stays level
+        let right = transform.rotation_matrix() * Vector3::new(1.0, 0.0, 0.0);
+        assert!(right.z.abs() < 1e-3);
+    }
+}
+
+#[test]
+fn vector2_rotation(){
+    use std::f32::consts::FRAC_PI_2;
+    let x = Vector2::new(1.0, 0.0);
+    assert!(close2(x.rotate(FRAC_PI_2), Vector2::new(0.0, 1.0)));
+    assert_eq!(x.perp(), Vector2::new(0.0, 1.0));
+    assert!(close2(Vector2::from_angle(FRAC_PI_2), Vector2::new(0.0, 1.0)));
+
+    let mut rng = Rng::new();
+    for _ in 0..RUNS{
+        let v = rng.vec2(10.0);
+        let angle = rng.float(3.0);
+        assert!((v.rotate(angle).magnitude() - v.magnitude()).abs() < 1e-3);
+        assert!(close2(v.rotate(angle).rotate(-angle), v));
+        assert!(v.perp().dot(v).abs() < 1e-3);
+        assert!(close2(Vector2::from_angle(v.angle()) * v.magnitude(), v));
+    }
+}
+
+#[test]
+fn transform2d_matches_affine(){
+    let mut rng = Rng::new();
+    for _ in 0..RUNS{
+        let transform = rng.transform2d();
+        let v = rng.vec2(10.0);
+        let moved = transform.transform_point(v);
+        let affine = transform.to_affine();
+        assert!(close2(affine.transform_point(v), moved));
+        assert!(close2(affine.inverse().unwrap().transform_point(moved), v));
+        assert!(close2(transform.inverse_transform_point(moved), v));
+
+        let homogeneous = affine.to_mat3() * Vector3::new(v.x, v.y, 1.0);
+        assert!(close2(Vector2::new(homogeneous.x, homogeneous.y), moved));
+    }
+}
+
+#[test]
+fn transform2d_composition(){
+    let mut rng = Rng::new();
+    for _ in 0..RUNS{
+        // Parent scale has to be uniform, otherwise the exact result would be skewed
+        let mut parent = rng.transform2d();
+        let child = rng.transform2d();
+        let uniform = rng.float(1.0) + 2.0;
+        parent.scale = Vector2::new(uniform, uniform);
+        let v = rng.vec2(10.0);
+        let expected = parent.transform_point(child.transform_point(v));
+        assert!(close2((parent * child).transform_point(v), expected));
+        assert!(close2((parent.to_affine() * child.to_affine()).transform_point(v), expected));
+    }
+}
+
+#[test]
+fn rotate_around_pivot(){
+    let mut rng = Rng::new();
+    for _ in 0..RUNS{
+        let mut transform = rng.transform2d();
+        let pivot = rng.vec2(50.0);
+        let angle = rng.float(3.0);
+        let v = rng.vec2(10.0);
+        let before = transform.transform_point(v);
+        transform.rotate_around(pivot, angle);
+        assert!(close2(transform.transform_point(v), pivot + (before - pivot).rotate(angle)));
+    }
+}
+
+#[test]
+fn inverse_scales_with_magnitude(){
+    // Determinant of 1e-9 is below f32::EPSILON, but the Matrix is perfectly well-shaped
+    let tiny = Mat3::from_scale(Vector3::new(1e-3, 1e-3, 1e-3));
+    let inv = tiny.inverse().unwrap();
+    assert!(close(inv * Vector3::new(1e-3, 2e-3, 3e-3), Vector3::new(1.0, 2.0, 3.0)));
+    let inv = Mat4::from_mat3(tiny).inverse().unwrap();
+    assert!(close(inv.transform_point(Vector3::new(1e-3, 2e-3, 3e-3)), Vector3::new(1.0, 2.0, 3.0)));
+
+    // Huge entries don't hide a flat Matrix
+    let flat = Mat3::from_scale(Vector3::new(1e6, 1e6, 1e-4));
+    assert!(flat.inverse().is_none());
+    assert!(Mat4::from_mat3(flat).inverse().is_none());
+    assert!(Mat3::from_scale(Vector3::new(0.0, 0.0, 0.0)).inverse().is_none());
+    assert!(Mat4::from_mat3(Mat3::from_scale(Vector3::new(0.0, 0.0, 0.0))).inverse().is_none());
+}
+
+#[test]
+fn affine_inverse_scales_with_magnitude(){
+    let tiny = Transform2D{loc: Vector2::new(5.0, -3.0), rot: 0.5, scale: Vector2::new(1e-4, 1e-4)}.to_affine();
+    let v = Vector2::new(1.0, 2.0);
+    assert!(close2(tiny.inverse().unwrap().transform_point(tiny.transform_point(v)), v));
+
+    let flat = Transform2D{loc: Vector2::new(0.0, 0.0), rot: 0.0, scale: Vector2::new(1e6, 1e-4)}.to_affine();
+    assert!(flat.inverse().is_none());
+}
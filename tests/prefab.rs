@@ -0,0 +1,373 @@
+//! Checks for the Prefab file format and `PrefabLoader`
+
+use std::collections::HashMap;
+
+use manufacture_core::comp::{CMDSprite, PlayerController, Tags, Transform2D};
+use manufacture_core::resources::{PrefabLoader, PrefabRegistry};
+use manufacture_core::types::*;
+use manufacture_engine::ECS::entity::EntityBuilder;
+use manufacture_engine::prelude::Resource;
+
+const PREFABS: &str = "
+# Basic enemy
+[orc.Transform2D]
+loc = 4.0, 2.0
+rot = 0.5
+scale = 2.0, 2.0
+
+[orc.Tags]
+tags = Enemy, Orc
+
+[orc.CMDSprite]
+size_x = 2
+size_y = 1
+z_index = 3
+data = 'O' 00FF00 000000; ';' #FF0000 0000ff
+
+[elite_orc]
+extends = orc
+remove = CMDSprite
+
+[elite_orc.Transform2D]
+loc += 1.0, -1.0
+scale *= 1.5
+rot = 0.25
+
+[elite_orc.Tags]
+tags += Elite
+
+[elite_orc.PlayerController]
+pid = 2
+";
+
+fn def<'a>(defs: &'a [PrefabDef], id: &str) -> &'a PrefabDef{
+    defs.iter().find(|def| def.id == id).unwrap()
+}
+
+fn comp<C: PrefabComponent>(def: &PrefabDef) -> Result<C, PrefabError>{
+    let fields = def.components.iter().find(|fields| fields.comp() == C::ID).unwrap();
+    C::from_fields(fields)
+}
+
+fn parse_line(source: &str) -> usize{
+    match parse_prefabs("bad.txt", source){
+        Err(PrefabError::Parse{file, line, ..}) if file == "bad.txt" => line,
+        Err(err) => panic!("Expected a Parse error, found {:?}", err),
+        Ok(_) => panic!("Expected `{}` to fail", source),
+    }
+}
+
+#[test]
+fn valid_prefabs(){
+    let defs = parse_prefabs("prefabs.txt", PREFABS).unwrap();
+    assert_eq!(defs.iter().map(|def| def.id.as_str()).collect::<Vec<_>>(), vec!["orc", "elite_orc"]);
+
+    let orc = def(&defs, "orc");
+    assert_eq!((orc.file.as_str(), orc.line, orc.extends.clone()), ("prefabs.txt", 3, None));
+    assert_eq!(orc.components.iter().map(|fields| fields.comp()).collect::<Vec<_>>(), vec!["Transform2D", "Tags", "CMDSprite"]);
+
+    let transform: Transform2D = comp(orc).unwrap();
+    assert_eq!((transform.loc, transform.rot, transform.scale), (Vector2::new(4.0, 2.0), 0.5, Vector2::new(2.0, 2.0)));
+    let tags: Tags = comp(orc).unwrap();
+    assert!(tags.has_id("Enemy") && tags.has_id("Orc"));
+    assert_eq!(tags.len(), 2);
+
+    let elite = def(&defs, "elite_orc");
+    assert_eq!(elite.extends, Some(("orc".to_string(), 18)));
+    assert_eq!(elite.remove, vec!["CMDSprite".to_string()]);
+}
+
+#[test]
+fn field_ops(){
+    let mut loader = PrefabLoader::new();
+    let mut registry = PrefabRegistry::new();
+    let ids = loader.load_str("prefabs.txt", PREFABS, &mut registry).unwrap();
+    assert_eq!(ids, vec!["orc".to_string(), "elite_orc".to_string()]);
+    assert!(registry.contains("orc") && registry.contains("elite_orc"));
+
+    let elite = loader.get_def("elite_orc").unwrap();
+    assert_eq!(elite.components.iter().map(|fields| fields.comp()).collect::<Vec<_>>(), vec!["Transform2D", "Tags", "PlayerController"]);
+
+    // `+=` adds element-wise, `*=` multiplies the inherited value, `=` replaces it
+    let transform: Transform2D = comp(elite).unwrap();
+    assert_eq!((transform.loc, transform.rot, transform.scale), (Vector2::new(5.0, 1.0), 0.25, Vector2::new(3.0, 3.0)));
+    let tags: Tags = comp(elite).unwrap();
+    assert!(tags.has_id("Enemy") && tags.has_id("Orc") && tags.has_id("Elite"));
+    let controller: PlayerController = comp(elite).unwrap();
+    assert_eq!((controller.pid, controller.active), (2, true));
+}
+
+#[test]
+fn field_op_errors(){
+    let mut loader = PrefabLoader::new();
+    let mut registry = PrefabRegistry::new();
+    loader.load_str("base.txt", "[base.Transform2D]\nloc = 1.0, 2.0\n\n[base.Tags]\ntags = A\n", &mut registry).unwrap();
+
+    let cases = [
+        // Modifying a field the base doesn't have
+        ("[a]\nextends = base\n\n[a.Transform2D]\nscale *= 2.0\n", 5),
+        // Multiplying something that isn't a number
+        ("[a]\nextends = base\n\n[a.Tags]\ntags *= 2.0\n", 5),
+        // Modifying without a base at all
+        ("[a.Transform2D]\nloc += 1.0, 1.0\n", 2),
+    ];
+    for (source, line) in cases{
+        match loader.load_str("a.txt", source, &mut registry){
+            Err(PrefabError::Parse{line: found, ..}) => assert_eq!(found, line, "{}", source),
+            other => panic!("Expected a Parse error for `{}`, found {:?}", source, other),
+        }
+    }
+    assert!(!registry.contains("a"));
+
+    // `*=` by a single number scales every element, lists of the same length go element-wise
+    loader.load_str("b.txt", "[b]\nextends = base\n\n[b.Transform2D]\nloc *= 3.0\n", &mut registry).unwrap();
+    let transform: Transform2D = comp(loader.get_def("b").unwrap()).unwrap();
+    assert_eq!(transform.loc, Vector2::new(3.0, 6.0));
+    loader.load_str("c.txt", "[c]\nextends = b\n\n[c.Transform2D]\nloc *= 2.0, 0.5\n", &mut registry).unwrap();
+    let transform: Transform2D = comp(loader.get_def("c").unwrap()).unwrap();
+    assert_eq!(transform.loc, Vector2::new(6.0, 3.0));
+}
+
+#[test]
+fn sprite_inheritance(){
+    let mut loader = PrefabLoader::new();
+    let mut registry = PrefabRegistry::new();
+    loader.load_str("prefabs.txt", PREFABS, &mut registry).unwrap();
+    let source = "
+[orc_banner]
+extends = orc
+
+[orc_banner.CMDSprite]
+size_x = 4
+data += '|' FFFFFF 000000; ';' 0000FF 000000
+
+[orc_banner.Tags]
+tags += Banner
+";
+    loader.load_str("banner.txt", source, &mut registry).unwrap();
+    let banner = loader.get_def("orc_banner").unwrap();
+
+    // Pixels get appended with `;`, other lists keep using `,`
+    assert_eq!(loader.separator("CMDSprite", "data"), "; ");
+    assert_eq!(loader.separator("Tags", "tags"), ", ");
+    let sprite: CMDSprite = comp(banner).unwrap();
+    assert_eq!((sprite.size_x, sprite.size_y, sprite.z_index), (4, 1, 3));
+    assert_eq!(sprite.data, vec![
+        ('O', (0, 255, 0), (0, 0, 0)),
+        (';', (255, 0, 0), (0, 0, 255)),
+        ('|', (255, 255, 255), (0, 0, 0)),
+        (';', (0, 0, 255), (0, 0, 0)),
+    ]);
+    let tags: Tags = comp(banner).unwrap();
+    assert!(tags.has_id("Orc") && tags.has_id("Banner"));
+}
+
+#[test]
+fn parse_errors(){
+    assert_eq!(parse_line("[orc.Transform2D\nloc = 0.0, 0.0\n"), 1);
+    assert_eq!(parse_line("\n[orc-1]\n"), 2);
+    assert_eq!(parse_line("[orc.Trans form]\n"), 1);
+    assert_eq!(parse_line("[orc.Tags]\ntags = A\n[orc.Tags]\n"), 3);
+    assert_eq!(parse_line("[orc.Tags]\ntags = A\ntags = B\n"), 3);
+    assert_eq!(parse_line("tags = A\n"), 1);
+    assert_eq!(parse_line("[orc.Tags]\ntags A\n"), 2);
+    assert_eq!(parse_line("[orc]\nhealth = 10\n"), 2);
+    assert_eq!(parse_line("[orc]\nextends = not an id\n"), 2);
+    assert_eq!(parse_line("[orc.Tags]\nta gs = A\n"), 2);
+
+    // Fields are checked when the Component is built
+    let defs = parse_prefabs("bad.txt", "[orc.Transform2D]\nloc = 1.0\nspeed = 2.0\n").unwrap();
+    assert!(matches!(comp::<Transform2D>(&defs[0]), Err(PrefabError::Parse{line: 3, ..})));
+    let defs = parse_prefabs("bad.txt", "[orc.Transform2D]\nloc = 1.0\n").unwrap();
+    assert!(matches!(comp::<Transform2D>(&defs[0]), Err(PrefabError::Parse{line: 2, ..})));
+    let defs = parse_prefabs("bad.txt", "[orc.PlayerController]\nactive = maybe\n").unwrap();
+    assert!(matches!(comp::<PlayerController>(&defs[0]), Err(PrefabError::Parse{line: 2, ..})));
+}
+
+#[test]
+fn loader_errors(){
+    let mut loader = PrefabLoader::new();
+    let mut registry = PrefabRegistry::new();
+    loader.load_str("prefabs.txt", PREFABS, &mut registry).unwrap();
+
+    assert_eq!(
+        loader.load_str("again.txt", "[orc.Tags]\ntags = A\n", &mut registry),
+        Err(PrefabError::DuplicateID("orc".to_string()))
+    );
+    assert!(matches!(loader.load_file("/no/such/prefabs.txt", &mut registry), Err(PrefabError::Io{..})));
+    assert!(matches!(
+        loader.load_str("health.txt", "[slime.Health]\nhp = 10\n", &mut registry),
+        Err(PrefabError::Parse{line: 1, ..})
+    ));
+
+    // Nothing from a broken file gets registered
+    assert!(loader.load_str("half.txt", "[ok.Tags]\ntags = A\n\n[broken.Health]\n", &mut registry).is_err());
+    assert!(!registry.contains("ok") && loader.get_def("ok").is_none());
+}
+
+#[test]
+fn errors_display(){
+    assert_eq!(PrefabError::DuplicateID("orc".to_string()).to_string(), "Prefab `orc` is already registered");
+    assert_eq!(PrefabError::DefaultID("Orc".to_string()).to_string(), "Prefab `Orc` has to override the default ID to be registered");
+    assert_eq!(
+        PrefabError::Io{file: "a.txt".to_string(), message: "not found".to_string()}.to_string(),
+        "a.txt: not found"
+    );
+    assert_eq!(
+        PrefabError::Parse{file: "a.txt".to_string(), line: 4, message: "oops".to_string()}.to_string(),
+        "a.txt:4: oops"
+    );
+}
+
+#[test]
+fn sprite_data(){
+    let defs = parse_prefabs("prefabs.txt", PREFABS).unwrap();
+    let sprite: CMDSprite = comp(def(&defs, "orc")).unwrap();
+    assert_eq!((sprite.size_x, sprite.size_y, sprite.z_index), (2, 1, 3));
+    assert_eq!(sprite.data, vec![('O', (0, 255, 0), (0, 0, 0)), (';', (255, 0, 0), (0, 0, 255))]);
+
+    // Without `data` the Sprite is filled with blank pixels
+    let defs = parse_prefabs("blank.txt", "[box.CMDSprite]\nsize_x = 2\nsize_y = 2\n").unwrap();
+    let sprite: CMDSprite = comp(&defs[0]).unwrap();
+    assert_eq!(sprite.data, vec![(' ', (255, 255, 255), (0, 0, 0)); 4]);
+
+    let broken = [
+        // Not enough pixels for the size
+        "[box.CMDSprite]\nsize_x = 2\nsize_y = 1\ndata = 'a' FFFFFF 000000\n",
+        // Unquoted character
+        "[box.CMDSprite]\nsize_x = 1\nsize_y = 1\ndata = a FFFFFF 000000\n",
+        // Bad color
+        "[box.CMDSprite]\nsize_x = 1\nsize_y = 1\ndata = 'a' FFFFFG 000000\n",
+        // Missing background
+        "[box.CMDSprite]\nsize_x = 1\nsize_y = 1\ndata = 'a' FFFFFF\n",
+        // Missing size
+        "[box.CMDSprite]\nsize_x = 1\n",
+    ];
+    for source in broken{
+        let defs = parse_prefabs("box.txt", source).unwrap();
+        assert!(comp::<CMDSprite>(&defs[0]).is_err(), "{}", source);
+    }
+}
+
+#[test]
+fn resolve_without_files(){
+    let defs = parse_prefabs("prefabs.txt", PREFABS).unwrap();
+    let resolved = resolve_prefabs(&defs, &HashMap::new(), &|_, _| ", ").unwrap();
+    assert!(resolved.iter().all(|def| def.extends.is_none() && def.remove.is_empty()));
+}
+
+#[test]
+fn resolve_duplicate_ids(){
+    let a = parse_prefabs("a.txt", "[orc.Tags]\ntags = A\n").unwrap();
+    let b = parse_prefabs("b.txt", "[orc.Tags]\ntags = B\n").unwrap();
+    let defs = [a[0].clone(), b[0].clone()];
+    assert_eq!(resolve_prefabs(&defs, &HashMap::new(), &|_, _| ", ").err(), Some(PrefabError::DuplicateID("orc".to_string())));
+}
+
+#[test]
+fn resolve_extends_chain(){
+    let source = "
+[c]
+extends = b
+
+[c.Transform2D]
+loc += 1.0, 1.0
+
+[b]
+extends = a
+remove = Tags
+
+[b.Transform2D]
+loc *= 2.0
+
+[a.Transform2D]
+loc = 1.0, 2.0
+
+[a.Tags]
+tags = A
+";
+    let defs = parse_prefabs("chain.txt", source).unwrap();
+    let resolved = resolve_prefabs(&defs, &HashMap::new(), &|_, _| ", ").unwrap();
+    // Order of the input is kept, even though `c` needs `b` and `a` resolved first
+    assert_eq!(resolved.iter().map(|def| def.id.as_str()).collect::<Vec<_>>(), vec!["c", "b", "a"]);
+
+    let [c, b, a] = [&resolved[0], &resolved[1], &resolved[2]];
+    assert_eq!(comp::<Transform2D>(a).unwrap().loc, Vector2::new(1.0, 2.0));
+    assert_eq!(comp::<Transform2D>(b).unwrap().loc, Vector2::new(2.0, 4.0));
+    assert_eq!(comp::<Transform2D>(c).unwrap().loc, Vector2::new(3.0, 5.0));
+    assert_eq!(a.components.len(), 2);
+    assert!(b.components.iter().chain(c.components.iter()).all(|fields| fields.comp() != "Tags"));
+
+    // Bases can come from Prefabs resolved before
+    let known = resolved.into_iter().map(|def| (def.id.clone(), def)).collect::<HashMap<_, _>>();
+    let defs = parse_prefabs("more.txt", "[d]\nextends = c\n\n[d.Tags]\ntags = D\n").unwrap();
+    let d = resolve_prefabs(&defs, &known, &|_, _| ", ").unwrap().remove(0);
+    assert_eq!(comp::<Transform2D>(&d).unwrap().loc, Vector2::new(3.0, 5.0));
+    assert!(comp::<Tags>(&d).unwrap().has_id("D"));
+}
+
+#[test]
+fn resolve_remove(){
+    let resolve = |source: &str| resolve_prefabs(&parse_prefabs("remove.txt", source)?, &HashMap::new(), &|_, _| ", ");
+
+    let ok = resolve("[a.Tags]\ntags = A\n\n[a.Transform2D]\n\n[b]\nextends = a\nremove = Tags, Transform2D\n").unwrap();
+    assert!(ok[1].components.is_empty());
+
+    // Removing something the base doesn't have
+    assert!(matches!(resolve("[a.Tags]\n\n[b]\nextends = a\nremove = CMDSprite\n"), Err(PrefabError::Parse{line: 4, ..})));
+    // Removing without a base
+    assert!(matches!(resolve("[b]\nremove = Tags\n"), Err(PrefabError::Parse{line: 1, ..})));
+    // Unknown base
+    assert!(matches!(resolve("[b]\nextends = a\n"), Err(PrefabError::Parse{line: 2, ..})));
+}
+
+#[test]
+fn resolve_cycles(){
+    let resolve = |source: &str| resolve_prefabs(&parse_prefabs("cycle.txt", source)?, &HashMap::new(), &|_, _| ", ");
+    let message = |result: Result<Vec<PrefabDef>, PrefabError>| match result{
+        Err(PrefabError::Parse{message, ..}) => message,
+        _ => panic!("Expected a cycle to be found"),
+    };
+
+    assert_eq!(message(resolve("[a]\nextends = a\n")), "Prefab inheritance cycle: a -> a");
+    assert_eq!(message(resolve("[a]\nextends = b\n\n[b]\nextends = a\n")), "Prefab inheritance cycle: a -> b -> a");
+    assert_eq!(
+        message(resolve("[x]\nextends = a\n\n[a]\nextends = b\n\n[b]\nextends = c\n\n[c]\nextends = a\n")),
+        "Prefab inheritance cycle: x -> a -> b -> c -> a"
+    );
+}
+
+struct Orc;
+impl EntityPrefab for Orc{
+    const ID: &'static str = "Orc";
+    fn spawn(&self, _builder: EntityBuilder<'_>) {}
+}
+
+/// Keeps the default `ID`
+struct Unnamed;
+impl EntityPrefab for Unnamed{
+    fn spawn(&self, _builder: EntityBuilder<'_>) {}
+}
+
+#[test]
+fn registry(){
+    let mut registry = PrefabRegistry::new();
+    assert_eq!(Unnamed::ID, DEFAULT_PREFAB_ID);
+
+    registry.register(Orc).unwrap();
+    // Prefabs left with the default ID would clash with each other
+    assert_eq!(registry.register(Unnamed), Err(PrefabError::DefaultID(std::any::type_name::<Unnamed>().to_string())));
+    assert!(registry.contains("Orc") && !registry.contains(DEFAULT_PREFAB_ID));
+    assert!(registry.get("Orc").is_some() && registry.get("Goblin").is_none());
+    assert_eq!(registry.ids().collect::<Vec<_>>(), vec!["Orc"]);
+
+    assert_eq!(registry.register(Orc), Err(PrefabError::DuplicateID("Orc".to_string())));
+    assert_eq!(
+        registry.register_spawner("Orc", std::sync::Arc::new(|_| {})),
+        Err(PrefabError::DuplicateID("Orc".to_string()))
+    );
+
+    registry.unregister("Orc");
+    assert!(!registry.contains("Orc"));
+    registry.register(Orc).unwrap();
+}
@@ -0,0 +1,131 @@
+//! Checks for `Transform2DPropagator` and `Transform3DPropagator` on nested hierarchies
+
+mod common;
+
+use common::*;
+use manufacture_core::commands::*;
+use manufacture_core::comp::*;
+use manufacture_core::events::EntitySpawned;
+use manufacture_core::systems::{Transform2DPropagator, Transform3DPropagator};
+use manufacture_core::types::*;
+use manufacture_engine::prelude::*;
+
+fn spawn_many(world: &mut World, count: usize) -> Vec<Token>{
+    for _ in 0..count{
+        run(world, Spawn);
+    }
+    events::<EntitySpawned, _>(world, |event| event.0)
+}
+
+fn transform(world: &World, token: Token, loc: Vector2, rot: f32, scale: f32){
+    world.fetch_mut::<Transform2D>().insert(token.id(), Transform2D{loc, rot, scale: Vector2::new(scale, scale)});
+}
+
+fn global(world: &World, token: Token) -> Option<Vector2>{
+    world.fetch::<GlobalTransform2D>().get(&token.id()).map(|global| global.loc)
+}
+
+fn close(a: Option<Vector2>, b: Vector2) -> bool{
+    a.is_some_and(|a| (a - b).magnitude() < 1e-4)
+}
+
+#[test]
+fn nested_2d(){
+    let mut world = world();
+    let tokens = spawn_many(&mut world, 4);
+    let [root, child, grandchild, loose] = [tokens[0], tokens[1], tokens[2], tokens[3]];
+    transform(&world, root, Vector2::new(10.0, 0.0), std::f32::consts::FRAC_PI_2, 2.0);
+    transform(&world, child, Vector2::new(1.0, 0.0), 0.0, 1.0);
+    transform(&world, grandchild, Vector2::new(1.0, 0.0), 0.0, 0.5);
+    transform(&world, loose, Vector2::new(-3.0, 3.0), 0.0, 1.0);
+    run(&mut world, AttachChild{parent: root, child});
+    run(&mut world, AttachChild{parent: child, child: grandchild});
+
+    run_system::<Transform2DPropagator>(&mut world);
+    assert!(close(global(&world, root), Vector2::new(10.0, 0.0)));
+    // Parent's rotation turns the offset, it's scale stretches it
+    assert!(close(global(&world, child), Vector2::new(10.0, 2.0)));
+    assert!(close(global(&world, grandchild), Vector2::new(10.0, 4.0)));
+    assert!(close(global(&world, loose), Vector2::new(-3.0, 3.0)));
+    let scale = world.fetch::<GlobalTransform2D>().get(&grandchild.id()).unwrap().scale;
+    assert_eq!(scale, Vector2::new(1.0, 1.0));
+}
+
+#[test]
+fn missing_transforms_2d(){
+    let mut world = world();
+    let tokens = spawn_many(&mut world, 5);
+    let [bare_root, child, root, bare_middle, leaf] = [tokens[0], tokens[1], tokens[2], tokens[3], tokens[4]];
+    transform(&world, child, Vector2::new(3.0, 4.0), 0.0, 1.0);
+    transform(&world, root, Vector2::new(5.0, 0.0), 0.0, 2.0);
+    transform(&world, leaf, Vector2::new(1.0, 0.0), 0.0, 1.0);
+    run(&mut world, AttachChild{parent: bare_root, child});
+    run(&mut world, AttachChild{parent: root, child: bare_middle});
+    run(&mut world, AttachChild{parent: bare_middle, child: leaf});
+
+    run_system::<Transform2DPropagator>(&mut world);
+    // A root without a Transform sits at the origin
+    assert!(close(global(&world, child), Vector2::new(3.0, 4.0)));
+    // Entities without a Transform pass their parent's one down, but don't get a Global one themselves
+    assert!(close(global(&world, leaf), Vector2::new(7.0, 0.0)));
+    assert_eq!((global(&world, bare_root), global(&world, bare_middle)), (None, None));
+}
+
+#[test]
+fn orphaned_globals_are_removed(){
+    let mut world = world();
+    let tokens = spawn_many(&mut world, 3);
+    let [root, child, other] = [tokens[0], tokens[1], tokens[2]];
+    transform(&world, root, Vector2::new(1.0, 1.0), 0.0, 1.0);
+    transform(&world, child, Vector2::new(1.0, 0.0), 0.0, 1.0);
+    transform(&world, other, Vector2::new(0.0, 0.0), 0.0, 1.0);
+    run(&mut world, AttachChild{parent: root, child});
+
+    let mut system = Transform2DPropagator::new();
+    step(&mut world, &mut system);
+    assert!(close(global(&world, child), Vector2::new(2.0, 1.0)));
+
+    // The root loses it's Transform, the child now hangs off the origin
+    world.fetch_mut::<Transform2D>().remove(&root.id());
+    world.fetch_mut::<Transform2D>().remove(&other.id());
+    step(&mut world, &mut system);
+    assert_eq!((global(&world, root), global(&world, other)), (None, None));
+    assert!(close(global(&world, child), Vector2::new(1.0, 0.0)));
+
+    // Detached children become roots of their own
+    transform(&world, root, Vector2::new(5.0, 5.0), 0.0, 1.0);
+    run(&mut world, DetachChild(child));
+    step(&mut world, &mut system);
+    assert!(close(global(&world, root), Vector2::new(5.0, 5.0)));
+    assert!(close(global(&world, child), Vector2::new(1.0, 0.0)));
+}
+
+#[test]
+fn nested_3d(){
+    let mut world = world();
+    let tokens = spawn_many(&mut world, 4);
+    let [bare_root, root, bare_middle, leaf] = [tokens[0], tokens[1], tokens[2], tokens[3]];
+    let put = |token: Token, loc: Vector3, scale: f32| {
+        world.fetch_mut::<Transform3D>().insert(token.id(), Transform3D{
+            loc,
+            rot: Vector3::new(0.0, 0.0, 0.0),
+            scale: Vector3::new(scale, scale, scale),
+        });
+    };
+    put(root, Vector3::new(0.0, 0.0, 5.0), 2.0);
+    put(leaf, Vector3::new(1.0, 1.0, 1.0), 1.0);
+    run(&mut world, AttachChild{parent: bare_root, child: root});
+    run(&mut world, AttachChild{parent: root, child: bare_middle});
+    run(&mut world, AttachChild{parent: bare_middle, child: leaf});
+
+    let mut system = Transform3DPropagator::new();
+    step(&mut world, &mut system);
+    let global = |world: &World, token: Token| world.fetch::<GlobalTransform3D>().get(&token.id()).map(|global| global.loc);
+    assert_eq!(global(&world, root), Some(Vector3::new(0.0, 0.0, 5.0)));
+    assert_eq!(global(&world, leaf), Some(Vector3::new(2.0, 2.0, 7.0)));
+    assert_eq!((global(&world, bare_root), global(&world, bare_middle)), (None, None));
+
+    world.fetch_mut::<Transform3D>().remove(&leaf.id());
+    step(&mut world, &mut system);
+    assert_eq!(global(&world, leaf), None);
+}
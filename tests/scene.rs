@@ -0,0 +1,103 @@
+//! Checks for building Scenes and loading them from files
+
+use manufacture_core::resources::{PrefabLoader, PrefabRegistry, SceneRegistry};
+use manufacture_core::types::*;
+use manufacture_engine::ECS::entity::EntityBuilder;
+use manufacture_engine::prelude::Resource;
+
+const PREFABS: &str = "
+[orc.Transform2D]
+loc = 0.0, 0.0
+
+[orc.Tags]
+tags = Enemy
+";
+
+const LEVEL: &str = "
+# The player survives switching levels
+[player.PlayerController]
+pid = 1
+
+[player.Persistent]
+
+[orc_1]
+extends = orc
+
+[orc_2]
+extends = orc
+
+[orc_2.Transform2D]
+loc = 8.0, 2.0
+";
+
+struct Marker;
+impl EntityPrefab for Marker{
+    const ID: &'static str = "Marker";
+    fn spawn(&self, _builder: EntityBuilder<'_>) {}
+}
+
+fn loader() -> PrefabLoader{
+    let mut loader = PrefabLoader::new();
+    loader.load_str("prefabs.txt", PREFABS, &mut PrefabRegistry::new()).unwrap();
+    loader
+}
+
+#[test]
+fn scene_from_code(){
+    let mut scenes = SceneRegistry::new();
+    let scene = Scene::new("menu").with(Marker).with(Marker);
+    assert_eq!((scene.id(), scene.len()), ("menu", 2));
+
+    assert!(scenes.register(scene).is_none());
+    assert!(scenes.contains("menu"));
+    assert!(!scenes.is_loaded("menu"));
+    assert_eq!(scenes.loaded().count(), 0);
+
+    // Registering under the same ID replaces the old Scene
+    let old = scenes.register(Scene::new("menu")).unwrap();
+    assert_eq!(old.len(), 2);
+    assert!(scenes.get("menu").unwrap().is_empty());
+    assert_eq!(scenes.unregister("menu").map(|scene| scene.len()), Some(0));
+    assert!(!scenes.contains("menu"));
+}
+
+#[test]
+fn scene_from_text(){
+    let mut scenes = SceneRegistry::new();
+    loader().load_scene_str("level_1", "level_1.txt", LEVEL, &mut scenes).unwrap();
+    assert_eq!(scenes.get("level_1").unwrap().len(), 3);
+    assert_eq!(scenes.ids().collect::<Vec<_>>(), vec!["level_1"]);
+}
+
+#[test]
+fn broken_scene_files(){
+    let mut scenes = SceneRegistry::new();
+    let loader = loader();
+
+    let unknown_base = "[boss]\nextends = dragon\n";
+    assert!(matches!(
+        loader.load_scene_str("level_2", "level_2.txt", unknown_base, &mut scenes),
+        Err(PrefabError::Parse{line: 2, ..})
+    ));
+
+    let unknown_comp = "[boss.Health]\nhp = 10\n";
+    assert!(matches!(
+        loader.load_scene_str("level_2", "level_2.txt", unknown_comp, &mut scenes),
+        Err(PrefabError::Parse{line: 1, ..})
+    ));
+
+    let persistent_with_fields = "[player.Persistent]\nforever = true\n";
+    assert!(loader.load_scene_str("level_2", "level_2.txt", persistent_with_fields, &mut scenes).is_err());
+
+    assert!(matches!(
+        loader.load_scene_file("level_2", "/no/such/scene.txt", &mut scenes),
+        Err(PrefabError::Io{..})
+    ));
+    assert!(!scenes.contains("level_2"));
+}
+
+#[test]
+fn scene_errors_display(){
+    assert_eq!(CommandError::MissingScene("level_9".to_string()).to_string(), "Scene `level_9` is not registered");
+    assert_eq!(CommandError::SceneAlreadyLoaded("menu".to_string()).to_string(), "Scene `menu` is already loaded");
+}
@@ -0,0 +1,90 @@
+//! Checks for running Commands later with `CommandScheduler`
+
+mod common;
+
+use std::time::Duration;
+
+use common::*;
+use manufacture_core::commands::*;
+use manufacture_core::events::*;
+use manufacture_core::resources::{CommandScheduler, FrameTime};
+use manufacture_core::systems::ScheduledCommandRunner;
+use manufacture_engine::prelude::*;
+
+/// Run a single frame of the given length
+fn frame(world: &mut World, delta: Duration){
+    world.fetch_res_mut::<FrameTime>().set_delta(delta);
+    run_system::<ScheduledCommandRunner>(world);
+}
+
+fn spawned(world: &World) -> usize{
+    events::<EntitySpawned, _>(world, |event| event.0).len()
+}
+
+#[test]
+fn after_frames(){
+    let mut world = world();
+    let handle = world.fetch_res_mut::<CommandScheduler>().after_frames(2, Spawn);
+    world.fetch_res_mut::<CommandScheduler>().after_frames(0, Spawn);
+
+    frame(&mut world, Duration::ZERO);
+    assert_eq!(spawned(&world), 1);
+    assert!(world.fetch_res::<CommandScheduler>().is_pending(handle));
+
+    frame(&mut world, Duration::ZERO);
+    assert_eq!(spawned(&world), 2);
+    assert!(!world.fetch_res::<CommandScheduler>().is_pending(handle));
+    assert!(world.fetch_res::<CommandScheduler>().is_empty());
+    assert_eq!(world.fetch_res::<CommandScheduler>().frame(), 2);
+
+    frame(&mut world, Duration::ZERO);
+    assert_eq!(spawned(&world), 2);
+}
+
+#[test]
+fn after(){
+    let mut world = world();
+    let handle = world.fetch_res_mut::<CommandScheduler>().after(Duration::from_millis(50), Spawn);
+
+    frame(&mut world, Duration::from_millis(20));
+    frame(&mut world, Duration::from_millis(20));
+    assert_eq!(spawned(&world), 0);
+    assert!(world.fetch_res::<CommandScheduler>().is_pending(handle));
+
+    // Runs on the first frame past the delay, no matter how far past
+    frame(&mut world, Duration::from_millis(500));
+    assert_eq!(spawned(&world), 1);
+    assert!(!world.fetch_res::<CommandScheduler>().is_pending(handle));
+    assert_eq!(world.fetch_res::<CommandScheduler>().elapsed(), Duration::from_millis(540));
+
+    // Delays count from when the Command got scheduled
+    world.fetch_res_mut::<CommandScheduler>().after(Duration::from_millis(50), Spawn);
+    frame(&mut world, Duration::from_millis(40));
+    assert_eq!(spawned(&world), 1);
+    frame(&mut world, Duration::from_millis(10));
+    assert_eq!(spawned(&world), 2);
+}
+
+#[test]
+fn cancel(){
+    let mut world = world();
+    let (kept, cancelled) = {
+        let mut scheduler = world.fetch_res_mut::<CommandScheduler>();
+        (scheduler.after_frames(1, Spawn), scheduler.after(Duration::from_millis(10), Spawn))
+    };
+    assert_eq!(world.fetch_res::<CommandScheduler>().len(), 2);
+
+    assert!(world.fetch_res_mut::<CommandScheduler>().cancel(cancelled));
+    assert!(!world.fetch_res_mut::<CommandScheduler>().cancel(cancelled));
+    assert!(!world.fetch_res::<CommandScheduler>().is_pending(cancelled));
+
+    frame(&mut world, Duration::from_millis(20));
+    assert_eq!(spawned(&world), 1);
+    // Already ran
+    assert!(!world.fetch_res_mut::<CommandScheduler>().cancel(kept));
+
+    world.fetch_res_mut::<CommandScheduler>().after_frames(1, Spawn);
+    world.fetch_res_mut::<CommandScheduler>().clear();
+    frame(&mut world, Duration::ZERO);
+    assert_eq!(spawned(&world), 1);
+}
@@ -0,0 +1,65 @@
+//! Round trips for the `serde` feature, run with `cargo test --features serde`
+#![cfg(feature = "serde")]
+
+use manufacture_core::comp::*;
+use manufacture_core::types::*;
+
+fn round_trip<T: serde::Serialize + serde::de::DeserializeOwned>(value: &T) -> T{
+    let json = serde_json::to_string(value).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+struct Player;
+impl Tag for Player{
+    const ID: &'static str = "player";
+}
+
+#[test]
+fn vectors(){
+    assert_eq!(serde_json::to_string(&Vector2::new(1.5, -2.0)).unwrap(), r#"{"x":1.5,"y":-2.0}"#);
+    assert_eq!(round_trip(&Vector3::new(1.0, 2.0, 3.0)), Vector3::new(1.0, 2.0, 3.0));
+    assert_eq!(round_trip(&Vector2i::new(-4, 7)), Vector2i::new(-4, 7));
+}
+
+#[test]
+fn transforms(){
+    let transform = Transform2D{loc: Vector2::new(1.0, 2.0), rot: 0.5, scale: Vector2::new(1.0, 1.0)};
+    let loaded = round_trip(&transform);
+    assert_eq!((loaded.loc, loaded.rot, loaded.scale), (transform.loc, transform.rot, transform.scale));
+
+    let transform = Transform3D{loc: Vector3::new(1.0, 2.0, 3.0), rot: Vector3::new(0.1, 0.2, 0.3), scale: Vector3::new(2.0, 2.0, 2.0)};
+    let loaded = round_trip(&transform);
+    assert_eq!((loaded.loc, loaded.rot, loaded.scale), (transform.loc, transform.rot, transform.scale));
+}
+
+#[test]
+fn sprite_and_controller(){
+    let sprite = CMDSprite{size_x: 1, size_y: 1, z_index: 3, data: vec![('@', (255, 0, 0), (0, 0, 0))]};
+    let loaded = round_trip(&sprite);
+    assert_eq!((loaded.size_x, loaded.size_y, loaded.z_index), (1, 1, 3));
+    assert_eq!(loaded.data, sprite.data);
+
+    let controller = PlayerController{pid: 2, active: true};
+    let loaded = round_trip(&controller);
+    assert_eq!((loaded.pid, loaded.active), (2, true));
+}
+
+#[test]
+fn tags(){
+    let mut tags = Tags::new();
+    tags.tag::<Player>();
+    tags.tag_id("enemy");
+    assert_eq!(serde_json::to_string(&tags).unwrap(), r#"["enemy","player"]"#);
+
+    let loaded = round_trip(&tags);
+    assert!(loaded.has::<Player>());
+    assert!(loaded.has_id("enemy"));
+    assert!(!loaded.has_id("boss"));
+
+    // Loaded tags get interned
+    let again: Tags = serde_json::from_str(r#"["loaded-only"]"#).unwrap();
+    let id = TagId::get("loaded-only").unwrap();
+    assert!(again.contains(id));
+    assert_eq!(serde_json::to_string(&id).unwrap(), r#""loaded-only""#);
+    assert_eq!(serde_json::from_str::<TagId>(r#""loaded-only""#).unwrap(), id);
+}
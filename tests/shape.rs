@@ -0,0 +1,152 @@
+//! Checks for the geometric primitives
+
+use manufacture_core::types::*;
+
+fn v2(x: f32, y: f32) -> Vector2{
+    Vector2::new(x, y)
+}
+
+fn v3(x: f32, y: f32, z: f32) -> Vector3{
+    Vector3::new(x, y, z)
+}
+
+fn close(a: f32, b: f32) -> bool{
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn rect_queries(){
+    let a = Rect::new(v2(4.0, 4.0), v2(0.0, 0.0));
+    assert_eq!(a.min, v2(0.0, 0.0));
+    assert_eq!(a.center(), v2(2.0, 2.0));
+    assert_eq!(a.area(), 16.0);
+    assert_eq!(Rect::from_center(v2(2.0, 2.0), v2(4.0, 4.0)), a);
+
+    assert!(a.contains_point(v2(4.0, 0.0)));
+    assert!(!a.contains_point(v2(4.1, 0.0)));
+    assert!(a.contains(&Rect::new(v2(1.0, 1.0), v2(2.0, 2.0))));
+
+    let b = Rect::new(v2(3.0, -1.0), v2(6.0, 2.0));
+    assert!(a.intersects(&b));
+    assert_eq!(a.intersection(&b), Some(Rect::new(v2(3.0, 0.0), v2(4.0, 2.0))));
+    assert_eq!(a.union(&b), Rect::new(v2(0.0, -1.0), v2(6.0, 4.0)));
+    assert_eq!(a.intersection(&Rect::new(v2(5.0, 5.0), v2(6.0, 6.0))), None);
+
+    assert_eq!(a.expand(1.0), Rect::new(v2(-1.0, -1.0), v2(5.0, 5.0)));
+    assert_eq!(a.expand(-10.0), Rect::new(v2(2.0, 2.0), v2(2.0, 2.0)));
+    assert_eq!(a.expand_to(v2(-2.0, 1.0)), Rect::new(v2(-2.0, 0.0), v2(4.0, 4.0)));
+    assert_eq!(a.closest_point(v2(10.0, 2.0)), v2(4.0, 2.0));
+    assert_eq!(a.closest_point(v2(1.0, 2.0)), v2(1.0, 2.0));
+
+    assert_eq!(Aabb2::from_points([v2(1.0, 5.0), v2(-1.0, 2.0), v2(3.0, 0.0)]), Some(Rect::new(v2(-1.0, 0.0), v2(3.0, 5.0))));
+    assert_eq!(Aabb2::from_points([]), None);
+}
+
+#[test]
+fn aabb3_queries(){
+    let a = Aabb3::new(v3(0.0, 0.0, 0.0), v3(2.0, 2.0, 2.0));
+    let b = Aabb3::new(v3(1.0, 1.0, 1.0), v3(3.0, 3.0, 3.0));
+    assert_eq!(a.volume(), 8.0);
+    assert!(a.contains_point(v3(2.0, 1.0, 0.0)));
+    assert!(!a.contains_point(v3(1.0, 1.0, -0.1)));
+    assert_eq!(a.intersection(&b), Some(Aabb3::new(v3(1.0, 1.0, 1.0), v3(2.0, 2.0, 2.0))));
+    assert_eq!(a.union(&b), Aabb3::new(v3(0.0, 0.0, 0.0), v3(3.0, 3.0, 3.0)));
+    assert!(!a.intersects(&Aabb3::new(v3(0.0, 0.0, 3.0), v3(1.0, 1.0, 4.0))));
+    assert_eq!(a.closest_point(v3(-1.0, 1.0, 5.0)), v3(0.0, 1.0, 2.0));
+    assert_eq!(a.expand(0.5).size(), v3(3.0, 3.0, 3.0));
+}
+
+#[test]
+fn circle_queries(){
+    let a = Circle::new(v2(0.0, 0.0), 2.0);
+    let b = Circle::new(v2(3.0, 0.0), 1.0);
+    assert!(a.contains_point(v2(0.0, 2.0)));
+    assert!(!a.contains_point(v2(1.5, 1.5)));
+    assert!(a.intersects(&b));
+    assert!(!a.intersects(&Circle::new(v2(3.1, 0.0), 1.0)));
+    assert!(a.contains(&Circle::new(v2(1.0, 0.0), 1.0)));
+    assert!(a.intersects_aabb(&Rect::new(v2(1.0, 1.0), v2(5.0, 5.0))));
+    assert!(!a.intersects_aabb(&Rect::new(v2(1.5, 1.5), v2(5.0, 5.0))));
+    assert_eq!(a.bounds(), Rect::new(v2(-2.0, -2.0), v2(2.0, 2.0)));
+
+    let union = a.union(&b);
+    assert!(close(union.radius, 3.0) && close(union.center.x, 1.0));
+    assert!(union.contains(&a) && union.contains(&b));
+    assert_eq!(a.union(&Circle::new(v2(0.5, 0.0), 0.5)), a);
+
+    assert_eq!(a.expand(1.0).radius, 3.0);
+    assert_eq!(a.expand(-5.0).radius, 0.0);
+    assert_eq!(a.closest_point(v2(10.0, 0.0)), v2(2.0, 0.0));
+    assert_eq!(a.closest_point(v2(1.0, 0.0)), v2(1.0, 0.0));
+}
+
+#[test]
+fn sphere_queries(){
+    let a = Sphere::new(v3(0.0, 0.0, 0.0), 1.0);
+    let b = Sphere::new(v3(0.0, 0.0, 3.0), 1.0);
+    assert!(!a.intersects(&b));
+    assert!(a.intersects(&b.expand(1.0)));
+    let union = a.union(&b);
+    assert!(close(union.radius, 2.5) && close(union.center.z, 1.5));
+    assert_eq!(a.closest_point(v3(0.0, 5.0, 0.0)), v3(0.0, 1.0, 0.0));
+    assert!(a.intersects_aabb(&Aabb3::new(v3(0.5, 0.5, 0.5), v3(2.0, 2.0, 2.0))));
+    assert!(!a.intersects_aabb(&Aabb3::new(v3(0.7, 0.7, 0.7), v3(2.0, 2.0, 2.0))));
+}
+
+#[test]
+fn ray2_queries(){
+    let ray = Ray2::new(v2(0.0, 0.0), v2(2.0, 0.0));
+    assert_eq!(ray.dir(), v2(1.0, 0.0));
+    assert_eq!(ray.at(3.0), v2(3.0, 0.0));
+    assert_eq!(ray.closest_point(v2(2.0, 5.0)), v2(2.0, 0.0));
+    assert_eq!(ray.closest_point(v2(-2.0, 5.0)), v2(0.0, 0.0));
+
+    assert_eq!(ray.intersect_aabb(&Rect::new(v2(2.0, -1.0), v2(4.0, 1.0))), Some(2.0));
+    assert_eq!(ray.intersect_aabb(&Rect::new(v2(-4.0, -1.0), v2(-2.0, 1.0))), None);
+    // Grazing an edge still counts
+    assert_eq!(ray.intersect_aabb(&Rect::new(v2(2.0, 0.0), v2(4.0, 3.0))), Some(2.0));
+    assert_eq!(ray.intersect_aabb(&Rect::new(v2(-1.0, -1.0), v2(1.0, 1.0))), Some(0.0));
+
+    assert_eq!(ray.intersect_circle(&Circle::new(v2(5.0, 0.0), 1.0)), Some(4.0));
+    assert_eq!(ray.intersect_circle(&Circle::new(v2(5.0, 2.0), 1.0)), None);
+    assert_eq!(ray.intersect_circle(&Circle::new(v2(-5.0, 0.0), 1.0)), None);
+    assert_eq!(ray.intersect_circle(&Circle::new(v2(0.0, 0.0), 1.0)), Some(0.0));
+
+    assert_eq!(ray.intersect_segment(&Segment::new(v2(3.0, -1.0), v2(3.0, 1.0))), Some(3.0));
+    assert_eq!(ray.intersect_segment(&Segment::new(v2(3.0, 0.5), v2(3.0, 1.0))), None);
+    assert_eq!(ray.intersect_segment(&Segment::new(v2(-3.0, -1.0), v2(-3.0, 1.0))), None);
+    assert_eq!(ray.intersect_segment(&Segment::new(v2(2.0, 0.0), v2(5.0, 0.0))), Some(2.0));
+}
+
+#[test]
+fn ray3_queries(){
+    let ray = Ray3::towards(v3(0.0, 0.0, 10.0), v3(0.0, 0.0, 0.0));
+    assert_eq!(ray.dir(), v3(0.0, 0.0, -1.0));
+    assert_eq!(ray.intersect_sphere(&Sphere::new(v3(0.0, 0.0, 0.0), 2.0)), Some(8.0));
+    assert_eq!(ray.intersect_sphere(&Sphere::new(v3(3.0, 0.0, 0.0), 2.0)), None);
+    assert_eq!(ray.intersect_aabb(&Aabb3::new(v3(-1.0, -1.0, -1.0), v3(1.0, 1.0, 1.0))), Some(9.0));
+    assert_eq!(ray.intersect_aabb(&Aabb3::new(v3(2.0, -1.0, -1.0), v3(3.0, 1.0, 1.0))), None);
+
+    let diagonal = Ray3::new(v3(-5.0, -5.0, -5.0), v3(1.0, 1.0, 1.0));
+    let hit = diagonal.intersect_aabb(&Aabb3::new(v3(-1.0, -1.0, -1.0), v3(1.0, 1.0, 1.0))).unwrap();
+    assert!(diagonal.at(hit).approx_eq(v3(-1.0, -1.0, -1.0), 1e-4));
+}
+
+#[test]
+fn segment_queries(){
+    let a = Segment::new(v2(0.0, 0.0), v2(4.0, 4.0));
+    let b = Segment::new(v2(0.0, 4.0), v2(4.0, 0.0));
+    assert!(a.intersection(&b).unwrap().approx_eq(v2(2.0, 2.0), 1e-4));
+    assert!(!a.intersects(&Segment::new(v2(5.0, 0.0), v2(5.0, 10.0))));
+    // Would cross if `a` went on further
+    assert!(!a.intersects(&Segment::new(v2(6.0, 0.0), v2(6.0, 10.0))));
+    assert!(close(a.length(), 32.0f32.sqrt()));
+    assert_eq!(a.closest_point(v2(4.0, 0.0)), v2(2.0, 2.0));
+    assert_eq!(a.closest_point(v2(10.0, 10.0)), v2(4.0, 4.0));
+    assert!(close(a.distance_to_point(v2(-3.0, -4.0)), 5.0));
+    assert_eq!(a.bounds(), Rect::new(v2(0.0, 0.0), v2(4.0, 4.0)));
+
+    let c = Segment3::new(v3(0.0, 0.0, 0.0), v3(0.0, 0.0, 4.0));
+    assert_eq!(c.closest_point(v3(3.0, 0.0, 2.0)), v3(0.0, 0.0, 2.0));
+    assert!(close(c.distance_to_point(v3(0.0, 3.0, 8.0)), 5.0));
+}
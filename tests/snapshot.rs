@@ -0,0 +1,139 @@
+//! Checks for World Snapshot files and migrations, run with `cargo test --features serde`
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use manufacture_core::comp::*;
+use manufacture_core::resources::SnapshotRegistry;
+use manufacture_core::types::*;
+use manufacture_engine::prelude::{Component, Resource};
+use serde_json::json;
+
+fn record(id: usize, components: &[(&str, serde_json::Value)]) -> EntityRecord{
+    EntityRecord{
+        id,
+        components: components.iter().map(|(id, data)| (id.to_string(), data.clone())).collect(),
+    }
+}
+
+/// Version 1 stored the position as `pos`, version 2 calls it `loc`, version 3 adds `scale`
+fn registry_with_migrations() -> SnapshotRegistry{
+    let mut registry = SnapshotRegistry::new();
+    registry.add_component_migration::<Transform2D>(2, |mut data| {
+        data["scale"] = json!({"x": 1.0, "y": 1.0});
+        Ok(data)
+    });
+    registry.add_component_migration::<Transform2D>(1, |mut data| {
+        let pos = data.get_mut("pos").ok_or("missing `pos`")?.take();
+        data["loc"] = pos;
+        data.as_object_mut().unwrap().remove("pos");
+        Ok(data)
+    });
+    registry
+}
+
+#[test]
+fn json_round_trip(){
+    let mut snapshot = WorldSnapshot::new();
+    snapshot.component_versions.insert(Tags::ID.to_string(), 1);
+    snapshot.entities.push(record(4, &[(Tags::ID, json!(["player"]))]));
+    snapshot.resources.insert("Score".to_string(), json!(120));
+
+    let loaded = WorldSnapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+    assert_eq!(loaded, snapshot);
+
+    // Hand written files can leave out everything but the format
+    let minimal = WorldSnapshot::from_json(r#"{"format": 1}"#).unwrap();
+    assert!(minimal.entities.is_empty() && minimal.resources.is_empty());
+}
+
+#[test]
+fn rejects_newer_format(){
+    let source = format!(r#"{{"format": {}}}"#, SNAPSHOT_FORMAT + 1);
+    assert_eq!(
+        WorldSnapshot::from_json(&source),
+        Err(SnapshotError::UnsupportedFormat{found: SNAPSHOT_FORMAT + 1, supported: SNAPSHOT_FORMAT})
+    );
+    assert!(matches!(WorldSnapshot::from_json("{"), Err(SnapshotError::Parse(_))));
+}
+
+#[test]
+fn migrations_run_in_order(){
+    let registry = registry_with_migrations();
+    assert_eq!(registry.component_version::<Transform2D>(), Some(3));
+    assert_eq!(registry.component_version::<Tags>(), Some(1));
+    assert_eq!(registry.component_version::<Tween>(), None);
+
+    let mut snapshot = WorldSnapshot::new();
+    snapshot.component_versions.insert(Transform2D::ID.to_string(), 1);
+    snapshot.entities.push(record(0, &[
+        (Transform2D::ID, json!({"pos": {"x": 1.0, "y": 2.0}, "rot": 0.5})),
+        (Tags::ID, json!(["enemy"])),
+    ]));
+    registry.migrate(&mut snapshot).unwrap();
+
+    let data = snapshot.entities[0].components[Transform2D::ID].clone();
+    assert_eq!(data, json!({"loc": {"x": 1.0, "y": 2.0}, "rot": 0.5, "scale": {"x": 1.0, "y": 1.0}}));
+    let transform: Transform2D = serde_json::from_value(data).unwrap();
+    assert_eq!(transform.loc, Vector2::new(1.0, 2.0));
+    assert_eq!(snapshot.component_versions[Transform2D::ID], 3);
+
+    // Already up to date data is left alone
+    let before = snapshot.clone();
+    registry.migrate(&mut snapshot).unwrap();
+    assert_eq!(snapshot, before);
+}
+
+#[test]
+fn missing_versions_count_as_first(){
+    let registry = registry_with_migrations();
+    let mut snapshot = WorldSnapshot::new();
+    snapshot.entities.push(record(0, &[(Transform2D::ID, json!({"pos": {"x": 0.0, "y": 0.0}, "rot": 0.0}))]));
+    registry.migrate(&mut snapshot).unwrap();
+    assert!(snapshot.entities[0].components[Transform2D::ID].get("loc").is_some());
+}
+
+#[test]
+fn migration_errors(){
+    let registry = registry_with_migrations();
+    let snapshot_with = |version: u32, data: serde_json::Value| {
+        let mut snapshot = WorldSnapshot::new();
+        snapshot.component_versions.insert(Transform2D::ID.to_string(), version);
+        snapshot.entities.push(record(0, &[(Transform2D::ID, data)]));
+        snapshot
+    };
+
+    let mut snapshot = snapshot_with(4, json!({}));
+    assert_eq!(
+        registry.migrate(&mut snapshot),
+        Err(SnapshotError::NewerVersion{id: Transform2D::ID.to_string(), found: 4, supported: 3})
+    );
+
+    let mut snapshot = snapshot_with(1, json!({"rot": 0.0}));
+    assert_eq!(
+        registry.migrate(&mut snapshot),
+        Err(SnapshotError::Migration{id: Transform2D::ID.to_string(), from: 1, message: "missing `pos`".to_string()})
+    );
+
+    // Nothing migrates from version 0
+    let mut snapshot = snapshot_with(0, json!({}));
+    assert_eq!(
+        registry.migrate(&mut snapshot),
+        Err(SnapshotError::MissingMigration{id: Transform2D::ID.to_string(), from: 0})
+    );
+}
+
+#[test]
+fn unknown_entries(){
+    let registry = SnapshotRegistry::new();
+
+    let mut snapshot = WorldSnapshot::new();
+    snapshot.entities.push(record(0, &[("Health", json!(10))]));
+    assert_eq!(registry.migrate(&mut snapshot), Err(SnapshotError::UnknownComponent("Health".to_string())));
+
+    let mut snapshot = WorldSnapshot{
+        resources: BTreeMap::from([("Score".to_string(), json!(0))]),
+        ..WorldSnapshot::new()
+    };
+    assert_eq!(registry.migrate(&mut snapshot), Err(SnapshotError::UnknownResource("Score".to_string())));
+}
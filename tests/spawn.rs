@@ -0,0 +1,324 @@
+//! Checks for spawning and despawning Commands and the Events they send
+
+mod common;
+
+use common::*;
+use manufacture_core::commands::*;
+use manufacture_core::comp::{CMDSprite, Children, Parent, Persistent, PlayerController, SceneMember, TagId, Tags, Transform2D};
+use manufacture_core::events::*;
+use manufacture_core::resources::{CloneRegistry, DespawnSnapshots, PrefabLoader, PrefabRegistry, SceneRegistry, TagIndex};
+use manufacture_core::systems::ChangeAnnouncer;
+use manufacture_core::types::*;
+use manufacture_engine::prelude::*;
+
+#[test]
+fn spawn_and_despawn_announce_components(){
+    let mut world = world();
+    run(&mut world, SpawnPrefab(Orc{loc: Vector2::new(1.0, 2.0)}));
+    run(&mut world, SpawnPrefab(Hero));
+
+    let spawned = events::<EntitySpawned, _>(&world, |event| event.0);
+    let [orc, hero] = [spawned[0], spawned[1]];
+    assert_eq!(events::<ComponentAdded<Transform2D>, _>(&world, |event| event.0), vec![orc, hero]);
+    assert_eq!(events::<ComponentAdded<Tags>, _>(&world, |event| event.0), vec![orc]);
+    assert_eq!(events::<ComponentAdded<CMDSprite>, _>(&world, |event| event.0), vec![orc]);
+    assert_eq!(events::<ComponentAdded<PlayerController>, _>(&world, |event| event.0), vec![hero]);
+
+    run(&mut world, DespawnToken(orc));
+    assert_eq!(events::<EntityDespawned, _>(&world, |event| event.0), vec![orc]);
+    assert_eq!(events::<ComponentRemoved<Transform2D>, _>(&world, |event| event.0), vec![orc]);
+    assert_eq!(events::<ComponentRemoved<Tags>, _>(&world, |event| event.0), vec![orc]);
+    assert_eq!(events::<ComponentRemoved<CMDSprite>, _>(&world, |event| event.0), vec![orc]);
+    assert!(events::<ComponentRemoved<PlayerController>, _>(&world, |event| event.0).is_empty());
+
+    run(&mut world, DespawnID(hero.id()));
+    assert_eq!(events::<ComponentRemoved<Transform2D>, _>(&world, |event| event.0), vec![orc, hero]);
+    assert_eq!(events::<ComponentRemoved<PlayerController>, _>(&world, |event| event.0), vec![hero]);
+    assert!(!world.validate_token(&orc) && !world.validate_token(&hero));
+}
+
+#[test]
+fn other_spawn_paths_announce_components(){
+    let mut world = world();
+    world.fetch_res_mut::<PrefabRegistry>().register(Hero).unwrap();
+    run(&mut world, SpawnPrefabById::new("Hero"));
+    run(&mut world, SpawnPrefabWith::new(Hero).with(Tags::new()));
+    run(&mut world, Spawn);
+
+    let spawned = events::<EntitySpawned, _>(&world, |event| event.0);
+    assert_eq!(spawned.len(), 3);
+    run(&mut world, CloneEntity(spawned[1]));
+    let clone = events::<EntitySpawned, _>(&world, |event| event.0)[3];
+
+    // Clones don't get a PlayerController, see `CloneRegistry`
+    assert_eq!(events::<ComponentAdded<PlayerController>, _>(&world, |event| event.0), vec![spawned[0], spawned[1]]);
+    assert_eq!(events::<ComponentAdded<Transform2D>, _>(&world, |event| event.0), vec![spawned[0], spawned[1], clone]);
+    assert_eq!(events::<ComponentAdded<Tags>, _>(&world, |event| event.0), vec![spawned[1], clone]);
+}
+
+#[test]
+fn direct_storage_changes_are_announced(){
+    let mut world = world();
+    run(&mut world, SpawnPrefab(Hero));
+    let hero = events::<EntitySpawned, _>(&world, |event| event.0)[0];
+
+    // Like a System would, straight through the Storages
+    world.fetch_mut::<Tags>().insert(hero.id(), Tags::new());
+    world.fetch_mut::<PlayerController>().remove(&hero.id());
+    // Put in and taken out again before anyone could notice
+    world.fetch_mut::<Persistent>().insert(hero.id(), Persistent);
+    world.fetch_mut::<Persistent>().remove(&hero.id());
+    assert!(events::<ComponentAdded<Tags>, _>(&world, |event| event.0).is_empty());
+
+    run_system::<ChangeAnnouncer>(&mut world);
+    assert_eq!(events::<ComponentAdded<Tags>, _>(&world, |event| event.0), vec![hero]);
+    assert_eq!(events::<ComponentRemoved<PlayerController>, _>(&world, |event| event.0), vec![hero]);
+    assert!(events::<ComponentAdded<Persistent>, _>(&world, |event| event.0).is_empty());
+    assert!(events::<ComponentRemoved<Persistent>, _>(&world, |event| event.0).is_empty());
+
+    // Announced once only
+    run_system::<ChangeAnnouncer>(&mut world);
+    assert_eq!(events::<ComponentAdded<Tags>, _>(&world, |event| event.0).len(), 1);
+
+    // Despawned behind the Commands' back, the next Entity takes the ID over but not the removals
+    world.despawn(hero.id());
+    run(&mut world, Spawn);
+    let next = events::<EntitySpawned, _>(&world, |event| event.0)[1];
+    assert_eq!(next.id(), hero.id());
+    assert!(events::<ComponentRemoved<Transform2D>, _>(&world, |event| event.0).is_empty());
+    assert!(events::<ComponentRemoved<Tags>, _>(&world, |event| event.0).is_empty());
+}
+
+#[test]
+fn scene_membership_is_announced(){
+    let mut world = world();
+    world.fetch_res_mut::<SceneRegistry>().register(Scene::new("Level").with(Hero).with(Orc{loc: Vector2::new(0.0, 0.0)}));
+    run(&mut world, LoadScene::new("Level"));
+    let spawned = events::<EntitySpawned, _>(&world, |event| event.0);
+    assert_eq!(events::<ComponentAdded<SceneMember>, _>(&world, |event| event.0), spawned);
+
+    // The Persistent hero only loses it's membership, the orc despawns with it
+    run(&mut world, InsertComponent::new(spawned[0], Persistent));
+    run(&mut world, UnloadScene::new("Level"));
+    assert_eq!(events::<EntityDespawned, _>(&world, |event| event.0), vec![spawned[1]]);
+    assert_eq!(events::<ComponentRemoved<SceneMember>, _>(&world, |event| event.0), spawned);
+}
+
+#[test]
+fn despawn_snapshot_payload(){
+    let mut world = world();
+    {
+        let mut snapshots = world.fetch_res_mut::<DespawnSnapshots>();
+        snapshots.register::<Transform2D>();
+        snapshots.register::<PlayerController>();
+        // Registering twice doesn't capture twice
+        snapshots.register::<Transform2D>();
+    }
+    run(&mut world, SpawnPrefab(Orc{loc: Vector2::new(3.0, 4.0)}));
+    run(&mut world, SpawnPrefab(Hero));
+    let spawned = events::<EntitySpawned, _>(&world, |event| event.0);
+    run(&mut world, DespawnToken(spawned[0]));
+
+    let payload = |event: &EntityDespawned| (
+        event.0,
+        event.1.get::<Transform2D>().map(|transform| transform.loc),
+        event.1.get::<PlayerController>().map(|controller| controller.pid),
+        event.1.has::<Tags>()
+    );
+    assert_eq!(events(&world, payload), vec![(spawned[0], Some(Vector2::new(3.0, 4.0)), None, false)]);
+
+    // Components stop being captured once unregistered
+    world.fetch_res_mut::<DespawnSnapshots>().unregister::<Transform2D>();
+    run(&mut world, DespawnToken(spawned[1]));
+    assert_eq!(events(&world, payload)[1], (spawned[1], None, Some(1), false));
+
+    // Nothing is captured by default
+    let mut world = common::world();
+    run(&mut world, SpawnPrefab(Hero));
+    let hero = events::<EntitySpawned, _>(&world, |event| event.0)[0];
+    run(&mut world, DespawnToken(hero));
+    assert_eq!(events::<EntityDespawned, _>(&world, |event| event.1.is_empty()), vec![true]);
+}
+
+#[test]
+fn snapshot_take(){
+    let mut world = world();
+    world.fetch_res_mut::<DespawnSnapshots>().register::<Tags>();
+    run(&mut world, SpawnPrefab(Orc{loc: Vector2::new(0.0, 0.0)}));
+    let orc = events::<EntitySpawned, _>(&world, |event| event.0)[0];
+
+    let mut snapshot = world.fetch_res::<DespawnSnapshots>().capture(&world, orc.id());
+    let tags = snapshot.take::<Tags>().unwrap();
+    assert!(tags.has_id("Enemy"));
+    assert!(snapshot.take::<Tags>().is_none() && snapshot.is_empty());
+}
+
+#[test]
+fn spawn_prefab_by_id(){
+    let mut world = world();
+    world.fetch_res_mut::<PrefabRegistry>().register(Orc{loc: Vector2::new(7.0, 1.0)}).unwrap();
+    run(&mut world, SpawnPrefabById::new("Orc"));
+
+    let orc = events::<EntitySpawned, _>(&world, |event| event.0)[0];
+    assert_eq!(world.fetch::<Transform2D>().get(&orc.id()).map(|transform| transform.loc), Some(Vector2::new(7.0, 1.0)));
+    assert_eq!(world.fetch_res::<TagIndex>().entities(TagId::new("Enemy")).collect::<Vec<_>>(), vec![orc.id()]);
+
+    // Prefabs loaded from files are spawned the same way
+    world.fetch_res_mut::<PrefabLoader>().load_str("slime.txt", "[slime.Tags]\ntags = Slime\n", &mut world.fetch_res_mut::<PrefabRegistry>()).unwrap();
+    run(&mut world, SpawnPrefabById::new("slime"));
+    let slime = events::<EntitySpawned, _>(&world, |event| event.0)[1];
+    assert!(world.fetch::<Tags>().get(&slime.id()).is_some_and(|tags| tags.has_id("Slime")));
+
+    run(&mut world, SpawnPrefabById::new("Goblin"));
+    assert_eq!(events::<EntitySpawned, _>(&world, |event| event.0).len(), 2);
+    assert_eq!(
+        events::<CommandFailed, _>(&world, |event| event.1.clone()),
+        vec![CommandError::MissingPrefab("Goblin".to_string())]
+    );
+}
+
+#[test]
+fn spawn_prefab_with(){
+    let mut world = world();
+    let mut tags = Tags::new();
+    tags.tag_id("Boss");
+
+    let mut command = SpawnPrefabWith::new(Orc{loc: Vector2::new(1.0, 1.0)})
+        .with(tags)
+        .with(PlayerController{pid: 3, active: false})
+        .then(|world, token| {
+            if let Some(transform) = world.fetch_mut::<Transform2D>().get_mut(&token.id()){
+                transform.loc.x += 10.0;
+            }
+        })
+        // Runs after the previous one
+        .then(|world, token| {
+            if let Some(transform) = world.fetch_mut::<Transform2D>().get_mut(&token.id()){
+                transform.loc.y *= 5.0;
+            }
+        });
+    let handle = command.handle();
+    // Asking twice gives the same slot
+    let again = command.handle();
+    assert_eq!((handle.get(), again.get()), (None, None));
+    run(&mut world, command);
+
+    let orc = events::<EntitySpawned, _>(&world, |event| event.0)[0];
+    assert_eq!((handle.get(), again.get()), (Some(orc), Some(orc)));
+    assert_eq!(world.fetch::<Transform2D>().get(&orc.id()).map(|transform| transform.loc), Some(Vector2::new(11.0, 5.0)));
+    assert_eq!(world.fetch::<PlayerController>().get(&orc.id()).map(|controller| controller.pid), Some(3));
+    // `with` replaced the Prefab's Tags, the Index follows the final ones
+    let tags = world.fetch::<Tags>().get(&orc.id()).cloned().unwrap();
+    assert!(tags.has_id("Boss") && !tags.has_id("Enemy"));
+    let index = world.fetch_res::<TagIndex>();
+    assert_eq!(index.entities(TagId::new("Boss")).collect::<Vec<_>>(), vec![orc.id()]);
+    assert_eq!(index.count(TagId::new("Enemy")), 0);
+}
+
+#[test]
+fn spawn_prefab_with_filled_handle(){
+    let mut world = world();
+    let handle = SpawnHandle::new();
+    let command = SpawnPrefabWith::new(Hero).fill_handle(&handle);
+    assert!(handle.get().is_none());
+    run(&mut world, command);
+    let hero = events::<EntitySpawned, _>(&world, |event| event.0)[0];
+    assert_eq!(handle.get(), Some(hero));
+
+    // Without `handle` or `fill_handle` there's nothing to fill in
+    run(&mut world, SpawnPrefabWith::new(Hero));
+    assert_eq!(handle.get(), Some(hero));
+    assert_eq!(events::<EntitySpawned, _>(&world, |event| event.0).len(), 2);
+}
+
+#[test]
+fn handles_cross_threads(){
+    fn assert_send<T: Send>(){}
+    fn assert_shared<T: Send + Sync>(){}
+    assert_shared::<SpawnHandle>();
+    assert_send::<SpawnPrefabWith<Hero>>();
+
+    let handle = SpawnHandle::new();
+    let mut world = world();
+    let command = std::thread::spawn({
+        let handle = handle.clone();
+        move || SpawnPrefabWith::new(Hero).fill_handle(&handle).then(|_, _| {})
+    }).join().unwrap();
+    run(&mut world, command);
+    assert_eq!(handle.get(), events::<EntitySpawned, _>(&world, |event| event.0).first().copied());
+}
+
+#[test]
+fn after_spawn_commands_resolve_late(){
+    let mut world = world();
+    run(&mut world, SpawnPrefab(Hero));
+    let hero = events::<EntitySpawned, _>(&world, |event| event.0)[0];
+
+    // Everything below is sent before any of it runs, like Commands sent by Systems during a single frame
+    let first = SpawnHandle::new();
+    let second = SpawnHandle::new();
+    let frame: Vec<Box<dyn Command>> = vec![
+        Box::new(AfterSpawn::new(&first, move |token| AttachChild{parent: hero, child: token})),
+        Box::new(SpawnPrefabWith::new(Orc{loc: Vector2::new(0.0, 0.0)}).fill_handle(&first)),
+        Box::new(Spawn),
+        Box::new(SpawnPrefabWith::new(Orc{loc: Vector2::new(1.0, 0.0)}).fill_handle(&second)),
+        Box::new(AfterSpawn::new(&first, move |token| AttachChild{parent: hero, child: token})),
+        Box::new(AfterSpawn::new(&second, |token| InsertComponent::new(token, PlayerController{pid: 9, active: true}))),
+    ];
+    assert_eq!((first.get(), second.get()), (None, None));
+    for mut command in frame{
+        command.execute(&mut world);
+    }
+
+    let spawned = events::<EntitySpawned, _>(&world, |event| event.0);
+    assert_eq!((first.get(), second.get()), (Some(spawned[1]), Some(spawned[3])));
+    // The Command sent before the spawn had nothing to resolve yet
+    assert_eq!(events::<CommandFailed, _>(&world, |event| event.1.clone()), vec![CommandError::UnresolvedHandle]);
+
+    let children = world.fetch::<Children>().get(&hero.id()).map(|children| children.iter().copied().collect::<Vec<_>>());
+    assert_eq!(children, Some(vec![spawned[1]]));
+    assert_eq!(world.fetch::<PlayerController>().get(&spawned[3].id()).map(|controller| controller.pid), Some(9));
+    assert!(world.fetch::<PlayerController>().get(&spawned[2].id()).is_none());
+}
+
+#[test]
+fn clone_entity(){
+    let mut world = world();
+    run(&mut world, SpawnPrefab(Orc{loc: Vector2::new(2.0, 3.0)}));
+    let orc = events::<EntitySpawned, _>(&world, |event| event.0)[0];
+    run(&mut world, InsertComponent::new(orc, PlayerController{pid: 1, active: true}));
+    run(&mut world, SpawnPrefab(Hero));
+    let hero = events::<EntitySpawned, _>(&world, |event| event.0)[1];
+    run(&mut world, AttachChild{parent: hero, child: orc});
+
+    run(&mut world, CloneEntity(orc));
+    let clone = events::<EntitySpawned, _>(&world, |event| event.0)[2];
+    assert_ne!(clone, orc);
+
+    // Registered Components are copied
+    assert_eq!(world.fetch::<Transform2D>().get(&clone.id()).map(|transform| transform.loc), Some(Vector2::new(2.0, 3.0)));
+    assert!(world.fetch::<Tags>().get(&clone.id()).is_some_and(|tags| tags.has_id("Enemy")));
+    assert_eq!(world.fetch::<CMDSprite>().get(&clone.id()).map(|sprite| sprite.data.len()), Some(1));
+    assert_eq!(world.fetch_res::<TagIndex>().count(TagId::new("Enemy")), 2);
+    // The player keeps controlling only the original, and the clone stays out of the hierarchy
+    assert!(world.fetch::<PlayerController>().get(&clone.id()).is_none());
+    assert!(world.fetch::<Parent>().get(&clone.id()).is_none());
+    assert_eq!(world.fetch::<Children>().get(&hero.id()).map(|children| children.len()), Some(1));
+
+    // Copies are independent
+    if let Some(transform) = world.fetch_mut::<Transform2D>().get_mut(&clone.id()){
+        transform.loc = Vector2::new(0.0, 0.0);
+    }
+    assert_eq!(world.fetch::<Transform2D>().get(&orc.id()).map(|transform| transform.loc), Some(Vector2::new(2.0, 3.0)));
+
+    // Registering a Component makes it cloned too
+    world.fetch_res_mut::<CloneRegistry>().register::<PlayerController>();
+    run(&mut world, CloneEntity(orc));
+    let second = events::<EntitySpawned, _>(&world, |event| event.0)[3];
+    assert_eq!(world.fetch::<PlayerController>().get(&second.id()).map(|controller| controller.pid), Some(1));
+
+    run(&mut world, DespawnToken(orc));
+    run(&mut world, CloneEntity(orc));
+    assert_eq!(events::<EntitySpawned, _>(&world, |event| event.0).len(), 4);
+    assert_eq!(events::<CommandFailed, _>(&world, |event| event.1.clone()), vec![CommandError::StaleToken(orc)]);
+}
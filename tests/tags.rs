@@ -0,0 +1,205 @@
+//! Checks for interned tags and `TagIndex`
+
+mod common;
+
+use common::*;
+use manufacture_core::commands::{Spawn, SpawnPrefab};
+use manufacture_core::comp::*;
+use manufacture_core::events::EntitySpawned;
+use manufacture_core::resources::TagIndex;
+use manufacture_core::systems::{ChangeAnnouncer, TagIndexer};
+use manufacture_core::types::{Vector2, WithTag, WithoutTag};
+use manufacture_engine::prelude::*;
+
+struct Player;
+impl Tag for Player{
+    const ID: &'static str = "player";
+}
+
+#[test]
+fn interning(){
+    let a = TagId::new("tags-test-a");
+    assert_eq!(TagId::new("tags-test-a"), a);
+    assert_eq!(TagId::new(&format!("tags-test-{}", "a")), a);
+    assert_ne!(TagId::new("tags-test-b"), a);
+
+    assert_eq!(&*a.name(), "tags-test-a");
+    assert_eq!(a.to_string(), "tags-test-a");
+    assert_eq!(TagId::from("tags-test-a"), a);
+
+    // Looking up doesn't intern
+    assert_eq!(TagId::get("tags-test-never-interned"), None);
+    assert_eq!(TagId::get("tags-test-never-interned"), None);
+    assert_eq!(TagId::get("tags-test-b"), Some(TagId::new("tags-test-b")));
+}
+
+#[test]
+fn tag_trait_api(){
+    let mut tags = Tags::new();
+    tags.tag::<Player>();
+    assert!(tags.has::<Player>());
+    assert!(tags.has_id("player"));
+    assert!(tags.contains(TagId::of::<Player>()));
+    assert_eq!(TagId::of::<Player>(), TagId::new(Player::ID));
+
+    tags.untag::<Player>();
+    assert!(!tags.has::<Player>());
+    assert!(tags.is_empty());
+}
+
+#[test]
+fn runtime_tags(){
+    let mut tags = Tags::new();
+    for faction in 0..3{
+        tags.tag_id(&format!("faction_{}", faction));
+    }
+    assert_eq!(tags.len(), 3);
+    assert!(tags.has_id("faction_1"));
+    assert!(!tags.has_id("faction_3"));
+
+    let faction = TagId::new("faction_2");
+    assert!(!tags.insert(faction));
+    assert!(tags.remove(faction));
+    assert!(!tags.remove(faction));
+    tags.untag_id("faction_0");
+    tags.untag_id("tags-test-unknown");
+
+    let names: Vec<String> = tags.iter().map(|tag| tag.to_string()).collect();
+    assert_eq!(names, vec!["faction_1".to_string()]);
+}
+
+#[test]
+fn interning_across_threads(){
+    let ids: Vec<TagId> = (0..8)
+        .map(|_| std::thread::spawn(|| TagId::new("tags-test-threaded")))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect();
+    assert!(ids.iter().all(|id| *id == ids[0]));
+}
+
+struct Enemy;
+impl Tag for Enemy{
+    const ID: &'static str = "enemy";
+}
+
+#[test]
+fn index_sync(){
+    let mut index = TagIndex::new();
+    let enemy = TagId::of::<Enemy>();
+    let player = TagId::of::<Player>();
+
+    let mut tags = Tags::new();
+    tags.tag::<Enemy>();
+    index.sync(3, Some(&tags));
+    index.sync(1, Some(&tags));
+    tags.tag::<Player>();
+    index.sync(2, Some(&tags));
+
+    assert_eq!(index.entities_with::<Enemy>().collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert_eq!(index.entities(player).collect::<Vec<_>>(), vec![2]);
+    assert_eq!(index.count(enemy), 3);
+    assert!(index.has(2, player) && !index.has(1, player));
+
+    // Re-syncing drops tags the Entity no longer has
+    tags.untag::<Enemy>();
+    index.sync(2, Some(&tags));
+    assert_eq!(index.tags_of(2).collect::<Vec<_>>(), vec![player]);
+    assert_eq!(index.count(enemy), 2);
+
+    index.sync(3, None);
+    assert_eq!(index.entities(enemy).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(index.tags_of(3).count(), 0);
+}
+
+#[test]
+fn index_insert_remove(){
+    let mut index = TagIndex::new();
+    let boss = TagId::new("tags-test-boss");
+    index.insert(7, boss);
+    index.insert(7, boss);
+    assert_eq!(index.count(boss), 1);
+
+    index.remove(7, boss);
+    index.remove(7, boss);
+    assert_eq!(index.count(boss), 0);
+    assert!(!index.has(7, boss));
+    assert_eq!(index.entities(TagId::new("tags-test-nobody")).count(), 0);
+}
+
+#[test]
+fn direct_changes(){
+    let mut world = world();
+    for _ in 0..3{
+        run(&mut world, Spawn);
+    }
+    let tokens = events::<EntitySpawned, _>(&world, |event| event.0);
+    let [a, b, c] = [tokens[0].id(), tokens[1].id(), tokens[2].id()];
+    let enemy = TagId::of::<Enemy>();
+
+    {
+        let mut storage = world.fetch_mut::<Tags>();
+        let mut tags = Tags::new();
+        tags.tag::<Enemy>();
+        storage.insert(a, tags.clone());
+        storage.insert(b, tags);
+    }
+    world.fetch_res_mut::<TagIndex>().sync(a, world.fetch::<Tags>().get(&a));
+    world.fetch_res_mut::<TagIndex>().sync(b, world.fetch::<Tags>().get(&b));
+    run_system::<TagIndexer>(&mut world);
+    assert!(world.fetch::<Tags>().get(&a).is_some_and(|tags| !tags.is_dirty()));
+
+    // Changed behind the index's back
+    if let Some(tags) = world.fetch_mut::<Tags>().get_mut(&a){
+        tags.untag::<Enemy>();
+    }
+    world.fetch_mut::<Tags>().insert(c, Tags::new());
+    if let Some(tags) = world.fetch_mut::<Tags>().get_mut(&c){
+        tags.tag::<Enemy>();
+    }
+
+    // Filters see the change right away
+    assert_eq!(query::<(&Tags,), WithTag<Enemy>>(&world), vec![b, c]);
+    assert_eq!(query::<(&Tags,), WithoutTag<Enemy>>(&world), vec![a]);
+    assert_eq!(world.fetch_res::<TagIndex>().entities(enemy).collect::<Vec<_>>(), vec![a, b]);
+
+    // The index catches up on the next frame
+    run_system::<TagIndexer>(&mut world);
+    assert_eq!(world.fetch_res::<TagIndex>().entities(enemy).collect::<Vec<_>>(), vec![b, c]);
+    assert!(world.fetch::<Tags>().get(&c).is_some_and(|tags| !tags.is_dirty()));
+    assert_eq!(query::<(&Tags,), WithTag<Enemy>>(&world), vec![b, c]);
+}
+
+#[test]
+fn removed_outside_commands(){
+    // The tag `Orc`s get from their Prefab
+    struct Hostile;
+    impl Tag for Hostile{
+        const ID: &'static str = "Enemy";
+    }
+    let mut world = world();
+    for x in 0..3{
+        run(&mut world, SpawnPrefab(Orc{loc: Vector2::new(x as f32, 0.0)}));
+    }
+    let tokens = events::<EntitySpawned, _>(&world, |event| event.0);
+    let [a, b, c] = [tokens[0].id(), tokens[1].id(), tokens[2].id()];
+    let hostile = TagId::of::<Hostile>();
+    assert_eq!(query::<(&Transform2D,), WithTag<Hostile>>(&world), vec![a, b, c]);
+
+    // Despawned behind the index's back
+    world.despawn(b);
+    run_system::<TagIndexer>(&mut world);
+    assert_eq!(world.fetch_res::<TagIndex>().entities(hostile).collect::<Vec<_>>(), vec![a, c]);
+
+    // Tags taken straight out of the Storage, Filters see it right away
+    world.fetch_mut::<Tags>().remove(&a);
+    assert_eq!(query::<(&Transform2D,), WithTag<Hostile>>(&world), vec![c]);
+    assert_eq!(query::<(&Transform2D,), WithoutTag<Hostile>>(&world), vec![a]);
+    assert_eq!(world.fetch_res::<TagIndex>().entities(hostile).collect::<Vec<_>>(), vec![a, c]);
+
+    // The index catches up at the end of the frame
+    run_system::<ChangeAnnouncer>(&mut world);
+    assert_eq!(world.fetch_res::<TagIndex>().entities(hostile).collect::<Vec<_>>(), vec![c]);
+    assert_eq!(world.fetch_res::<TagIndex>().tags_of(a).count(), 0);
+}
@@ -0,0 +1,175 @@
+//! Checks for interpolation, easing curves and `Tween` stepping
+
+use std::time::Duration;
+
+use manufacture_core::comp::*;
+use manufacture_core::types::*;
+
+const CURVES: [Easing; 16] = [
+    Easing::Linear,
+    Easing::QuadIn, Easing::QuadOut, Easing::QuadInOut,
+    Easing::CubicIn, Easing::CubicOut, Easing::CubicInOut,
+    Easing::ElasticIn, Easing::ElasticOut, Easing::ElasticInOut,
+    Easing::BounceIn, Easing::BounceOut, Easing::BounceInOut,
+    Easing::BackIn, Easing::BackOut, Easing::BackInOut,
+];
+
+fn close(a: f32, b: f32) -> bool{
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn curves_hit_both_ends(){
+    for curve in CURVES{
+        assert!(close(curve.apply(0.0), 0.0), "{curve:?} at 0");
+        assert!(close(curve.apply(1.0), 1.0), "{curve:?} at 1");
+        assert!(close(curve.apply(-5.0), 0.0), "{curve:?} clamps below");
+        assert!(close(curve.apply(5.0), 1.0), "{curve:?} clamps above");
+    }
+}
+
+#[test]
+fn in_out_curves_are_symmetric(){
+    for curve in [Easing::QuadInOut, Easing::CubicInOut, Easing::ElasticInOut, Easing::BounceInOut, Easing::BackInOut]{
+        assert!(close(curve.apply(0.5), 0.5), "{curve:?} at the middle");
+        for step in 0..=100{
+            let t = step as f32 / 100.0;
+            assert!(close(curve.apply(t), 1.0 - curve.apply(1.0 - t)), "{curve:?} at {t}");
+        }
+    }
+}
+
+#[test]
+fn out_mirrors_in(){
+    let pairs = [
+        (Easing::QuadIn, Easing::QuadOut),
+        (Easing::CubicIn, Easing::CubicOut),
+        (Easing::ElasticIn, Easing::ElasticOut),
+        (Easing::BounceIn, Easing::BounceOut),
+        (Easing::BackIn, Easing::BackOut),
+    ];
+    for (ease_in, ease_out) in pairs{
+        for step in 0..=100{
+            let t = step as f32 / 100.0;
+            assert!(close(ease_out.apply(t), 1.0 - ease_in.apply(1.0 - t)), "{ease_out:?} at {t}");
+        }
+    }
+    // Back pulls away from the target before going
+    assert!(Easing::BackIn.apply(0.2) < 0.0);
+    assert!(Easing::BackOut.apply(0.8) > 1.0);
+}
+
+#[test]
+fn vector_lerp(){
+    let (a, b) = (Vector3::new(1.0, 2.0, 3.0), Vector3::new(5.0, -2.0, 0.0));
+    assert_eq!(a.lerp(b, 0.0), a);
+    assert_eq!(a.lerp(b, 1.0), b);
+    assert_eq!(a.lerp(b, 0.5), Vector3::new(3.0, 0.0, 1.5));
+    assert_eq!(Vector2::new(0.0, 0.0).lerp(Vector2::new(2.0, 4.0), 0.25), Vector2::new(0.5, 1.0));
+}
+
+#[test]
+fn vector_slerp(){
+    use std::f32::consts::FRAC_1_SQRT_2;
+    let (x, y) = (Vector2::new(1.0, 0.0), Vector2::new(0.0, 2.0));
+    let half = x.slerp(y, 0.5);
+    assert!(close(half.magnitude(), 1.5));
+    assert!(close(half.angle(), std::f32::consts::FRAC_PI_4));
+
+    let (x, y) = (Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+    let half = x.slerp(y, 0.5);
+    assert!(close(half.x, FRAC_1_SQRT_2) && close(half.y, 0.0) && close(half.z, FRAC_1_SQRT_2));
+    assert!(close(x.slerp(y, 1.0).z, 1.0));
+    // Same direction falls back to lerp
+    assert_eq!(x.slerp(x * 3.0, 0.5), x * 2.0);
+}
+
+#[test]
+fn quat_slerp(){
+    let from = Quat::IDENTITY;
+    let to = Quat::from_euler(Vector3::new(0.0, 0.0, std::f32::consts::FRAC_PI_2));
+    let half = from.slerp(to, 0.5).to_euler();
+    assert!(close(half.z, std::f32::consts::FRAC_PI_4));
+    assert!(close(from.slerp(to, 1.0).to_euler().z, std::f32::consts::FRAC_PI_2));
+}
+
+fn tween(mode: TweenMode) -> Tween{
+    Tween::new(TweenTarget::Rot2D{from: 0.0, to: 10.0}, Duration::from_secs(1)).with_mode(mode)
+}
+
+fn transform() -> Transform2D{
+    Transform2D{loc: Vector2::new(0.0, 0.0), rot: 0.0, scale: Vector2::new(1.0, 1.0)}
+}
+
+#[test]
+fn tween_once(){
+    let mut tween = tween(TweenMode::Once);
+    let mut transform = transform();
+
+    assert_eq!(tween.advance(Duration::from_millis(250)), TweenState::Running);
+    tween.apply_2d(&mut transform);
+    assert!(close(transform.rot, 2.5));
+
+    assert_eq!(tween.advance(Duration::from_secs(5)), TweenState::Completed);
+    assert!(tween.is_finished());
+    tween.apply_2d(&mut transform);
+    assert!(close(transform.rot, 10.0));
+}
+
+#[test]
+fn tween_loop(){
+    let mut tween = tween(TweenMode::Loop);
+    let mut transform = transform();
+
+    assert_eq!(tween.advance(Duration::from_millis(1250)), TweenState::Looped);
+    assert!(!tween.is_finished());
+    tween.apply_2d(&mut transform);
+    assert!(close(transform.rot, 2.5));
+}
+
+#[test]
+fn tween_ping_pong(){
+    let mut tween = tween(TweenMode::PingPong);
+    let mut transform = transform();
+
+    assert_eq!(tween.advance(Duration::from_millis(1250)), TweenState::Looped);
+    assert!(tween.is_reversed());
+    tween.apply_2d(&mut transform);
+    assert!(close(transform.rot, 7.5));
+
+    assert_eq!(tween.advance(Duration::from_millis(1000)), TweenState::Looped);
+    assert!(!tween.is_reversed());
+    tween.apply_2d(&mut transform);
+    assert!(close(transform.rot, 2.5));
+}
+
+#[test]
+fn tween_ignores_other_targets(){
+    let tween = Tween::new(TweenTarget::Loc3D{from: Vector3::new(0.0, 0.0, 0.0), to: Vector3::new(1.0, 1.0, 1.0)}, Duration::ZERO);
+    let mut transform = transform();
+    tween.apply_2d(&mut transform);
+    assert_eq!(transform.loc, Vector2::new(0.0, 0.0));
+}
+
+#[test]
+fn tween_sprite_colors(){
+    let mut sprite = CMDSprite{
+        size_x: 2,
+        size_y: 1,
+        z_index: 0,
+        data: vec![('a', (0, 0, 0), (0, 0, 0)), ('b', (0, 0, 0), (9, 9, 9))],
+    };
+    let mut tween = Tween::new(TweenTarget::Foreground{from: (0, 0, 0), to: (200, 100, 255)}, Duration::from_secs(2));
+    tween.advance(Duration::from_secs(1));
+    tween.apply_sprite(&mut sprite);
+    assert_eq!(sprite.data[0].1, (100, 50, 128));
+    assert_eq!(sprite.data[1].1, (100, 50, 128));
+    assert_eq!(sprite.data[1].2, (9, 9, 9));
+
+    // Overshooting curves stay within the color range
+    let mut tween = Tween::new(TweenTarget::Background{from: (0, 0, 0), to: (255, 255, 255)}, Duration::from_secs(1))
+        .with_easing(Easing::BackOut);
+    tween.advance(Duration::from_millis(800));
+    tween.apply_sprite(&mut sprite);
+    assert_eq!(sprite.data[0].2, (255, 255, 255));
+}
@@ -0,0 +1,317 @@
+//! Property checks for the generic Vector types
+//!
+//! Properties run through `proptest`, which shrinks failing inputs down to a minimal case and reports it.
+//! Arithmetic properties use bounded values so they can't overflow, edge case properties use the full range,
+//! including `MIN`/`MAX`, NaN and infinities
+
+use manufacture_core::types::*;
+use proptest::prelude::*;
+
+fn close(a: f32, b: f32, scale: f32) -> bool{
+    (a - b).abs() <= 1e-3 * scale.max(1.0)
+}
+
+/// Floats small enough for arithmetic to stay exact-ish
+fn float() -> impl Strategy<Value = f32>{
+    -1000.0f32..1000.0
+}
+/// Any float at all, NaN and infinities included
+fn any_float() -> impl Strategy<Value = f32>{
+    prop::num::f32::ANY
+}
+/// Finite floats of any magnitude, zero and subnormals included
+fn finite_float() -> impl Strategy<Value = f32>{
+    prop::num::f32::NORMAL | prop::num::f32::SUBNORMAL | prop::num::f32::ZERO
+}
+/// Integers small enough for arithmetic not to overflow
+fn int() -> impl Strategy<Value = i32>{
+    -10_000i32..10_000
+}
+
+fn vec2() -> impl Strategy<Value = Vector2>{
+    (float(), float()).prop_map(|(x, y)| Vector2::new(x, y))
+}
+fn vec3() -> impl Strategy<Value = Vector3>{
+    (float(), float(), float()).prop_map(|(x, y, z)| Vector3::new(x, y, z))
+}
+fn any_vec3() -> impl Strategy<Value = Vector3>{
+    (any_float(), any_float(), any_float()).prop_map(|(x, y, z)| Vector3::new(x, y, z))
+}
+fn finite_vec3() -> impl Strategy<Value = Vector3>{
+    (finite_float(), finite_float(), finite_float()).prop_map(|(x, y, z)| Vector3::new(x, y, z))
+}
+fn vec2i() -> impl Strategy<Value = Vector2i>{
+    (int(), int()).prop_map(|(x, y)| Vector2i::new(x, y))
+}
+fn vec3i() -> impl Strategy<Value = Vector3i>{
+    (int(), int(), int()).prop_map(|(x, y, z)| Vector3i::new(x, y, z))
+}
+/// Integer Vectors over the whole `i32` range
+fn any_vec2i() -> impl Strategy<Value = Vector2i>{
+    any::<(i32, i32)>().prop_map(|(x, y)| Vector2i::new(x, y))
+}
+fn any_vec3i() -> impl Strategy<Value = Vector3i>{
+    any::<(i32, i32, i32)>().prop_map(|(x, y, z)| Vector3i::new(x, y, z))
+}
+
+proptest!{
+    #[test]
+    fn addition_is_commutative(a in vec3(), b in vec3(), c in vec2i(), d in vec2i()){
+        prop_assert_eq!(a + b, b + a);
+        prop_assert_eq!(c + d, d + c);
+    }
+
+    #[test]
+    fn integer_addition_is_associative(a in vec3i(), b in vec3i(), c in vec3i()){
+        prop_assert_eq!((a + b) + c, a + (b + c));
+    }
+
+    #[test]
+    fn subtraction_undoes_addition(a in vec3i(), b in vec3i()){
+        prop_assert_eq!(a + b - b, a);
+        let mut c = a;
+        c += b;
+        c -= b;
+        prop_assert_eq!(c, a);
+    }
+
+    #[test]
+    fn dot_is_symmetric(a in vec3(), b in vec3(), c in vec2i(), d in vec2i()){
+        prop_assert_eq!(a.dot(b), b.dot(a));
+        prop_assert_eq!(c.dot(d), d.dot(c));
+    }
+
+    #[test]
+    fn magnitude_matches_the_formula(v in vec3()){
+        let expected = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+        prop_assert!(close(v.magnitude(), expected, expected));
+    }
+
+    #[test]
+    fn normalize_has_unit_length(a in vec3(), b in vec2()){
+        prop_assume!(a.magnitude() >= 1e-3 && b.magnitude() >= 1e-3);
+        prop_assert!(close(a.normalize().magnitude(), 1.0, 1.0));
+        prop_assert!(close(b.normalize().magnitude(), 1.0, 1.0));
+    }
+
+    #[test]
+    fn cross_is_perpendicular(a in vec3i(), b in vec3i()){
+        let (a, b) = (Vec3::<i64>::new(a.x as i64, a.y as i64, a.z as i64), Vec3::<i64>::new(b.x as i64, b.y as i64, b.z as i64));
+        let c = a.cross(b);
+        prop_assert_eq!(c.dot(a), 0);
+        prop_assert_eq!(c.dot(b), 0);
+        prop_assert_eq!(b.cross(a), Vec3::new(-c.x, -c.y, -c.z));
+    }
+
+    #[test]
+    fn triangle_inequality_holds(a in vec3(), b in vec3()){
+        let sum = a.magnitude() + b.magnitude();
+        prop_assert!((a + b).magnitude() <= sum + 1e-3 * sum.max(1.0));
+    }
+
+    #[test]
+    fn grid_distances_agree(a in any_vec2i(), b in any_vec2i(), c in any_vec3i(), d in any_vec3i()){
+        let (manhattan, chebyshev) = (a.manhattan(b), a.chebyshev(b));
+        prop_assert!(manhattan >= chebyshev);
+        prop_assert!(manhattan as u64 <= chebyshev as u64 * 2);
+        prop_assert_eq!(manhattan, b.manhattan(a));
+        prop_assert_eq!(chebyshev, b.chebyshev(a));
+        prop_assert_eq!(a.manhattan(a), 0);
+
+        prop_assert!(c.manhattan(d) >= c.chebyshev(d));
+        prop_assert!(c.manhattan(d) as u64 <= c.chebyshev(d) as u64 * 3);
+    }
+
+    #[test]
+    fn floor_and_ceil_surround_the_value(v in vec3()){
+        let floor = Vector3i::from_vector(v, Rounding::Floor).to_vector();
+        let ceil = Vector3i::from_vector(v, Rounding::Ceil).to_vector();
+        prop_assert!(floor.x <= v.x && v.x <= ceil.x);
+        prop_assert!(floor.y <= v.y && v.y <= ceil.y);
+        prop_assert!(floor.z <= v.z && v.z <= ceil.z);
+    }
+
+    #[test]
+    fn rounding_clamps_out_of_range_values(v in any_vec3()){
+        let floor = Vector3i::from_vector(v, Rounding::Floor);
+        let ceil = Vector3i::from_vector(v, Rounding::Ceil);
+        // NaN has no order, it lands on 0 like with `as`
+        for (value, low, high) in [(v.x, floor.x, ceil.x), (v.y, floor.y, ceil.y), (v.z, floor.z, ceil.z)]{
+            match value.is_nan(){
+                true => prop_assert_eq!((low, high), (0, 0)),
+                false => prop_assert!(low <= high),
+            }
+        }
+    }
+
+    #[test]
+    fn integer_round_trip(v in any_vec3i()){
+        prop_assert_eq!(v.cast::<f64>().cast::<i32>(), v);
+
+        let unsigned = Vector3u::try_from(v);
+        prop_assert_eq!(unsigned.is_ok(), v.x >= 0 && v.y >= 0 && v.z >= 0);
+        if let Ok(unsigned) = unsigned{
+            prop_assert_eq!(Vector3i::try_from(unsigned), Ok(v));
+        }
+    }
+
+    #[test]
+    fn small_integers_survive_f32(v in vec3i()){
+        prop_assert_eq!(Vector3i::from_vector(v.into(), Rounding::Trunc), v);
+    }
+
+    #[test]
+    fn scalar_and_component_ops(v in vec2i()){
+        prop_assert_eq!(v * 2, v + v);
+        prop_assert_eq!(v * Vector2i::new(2, 2), v * 2);
+        prop_assert_eq!(v * 3 / 3, v);
+        let mut w = v;
+        w *= 4;
+        w /= Vector2i::new(2, 2);
+        prop_assert_eq!(w, v * 2);
+    }
+
+    #[test]
+    fn angles_stay_finite(v in vec3()){
+        // Parallel Vectors can round the cosine past 1
+        prop_assert!(!v.angle_between(v * 3.0).is_nan());
+        prop_assert!(!v.angle_between(-v).is_nan());
+    }
+
+    #[test]
+    fn try_normalize_is_safe(v in any_vec3()){
+        // Whatever goes in, what comes out is either nothing or a usable unit Vector
+        if let Some(unit) = v.try_normalize(){
+            prop_assert!(unit.x.is_finite() && unit.y.is_finite() && unit.z.is_finite(), "{:?} -> {:?}", v, unit);
+            prop_assert!(close(unit.magnitude(), 1.0, 1.0), "{:?} -> {:?}", v, unit);
+        }
+    }
+
+    #[test]
+    fn magnitude_does_not_overflow_early(v in finite_vec3()){
+        let magnitude = v.magnitude();
+        prop_assert!(!magnitude.is_nan());
+        prop_assert!(magnitude >= v.x.abs() && magnitude >= v.y.abs() && magnitude >= v.z.abs());
+        if v.x.abs().max(v.y.abs()).max(v.z.abs()) < f32::MAX / 2.0{
+            prop_assert!(magnitude.is_finite());
+        }
+    }
+
+    #[test]
+    fn magnitude_squared_matches(v in vec2()){
+        prop_assert!(close(v.magnitude() * v.magnitude(), v.magnitude_squared(), v.magnitude_squared()));
+    }
+
+    #[test]
+    fn clamp_stays_in_bounds(v in any_vec3i(), a in any_vec3i(), b in any_vec3i()){
+        let (low, high) = (a.min(b), a.max(b));
+        let clamped = v.clamp(low, high);
+        prop_assert_eq!(clamped.min(high), clamped);
+        prop_assert_eq!(clamped.max(low), clamped);
+    }
+
+    #[test]
+    fn negation(v in vec3i()){
+        prop_assert_eq!(-(-v), v);
+        prop_assert_eq!(v + -v, Vector3i::default());
+    }
+}
+
+#[test]
+fn display(){
+    assert_eq!(Vector2::new(1.5, -2.0).to_string(), "(1.5, -2)");
+    assert_eq!(Vector3i::new(1, 2, 3).to_string(), "(1, 2, 3)");
+    assert_eq!(Vec2::<u8>::new(255, 0).to_string(), "(255, 0)");
+}
+
+#[test]
+fn zero_vectors_stay_finite(){
+    let zero = Vector3::default();
+    assert_eq!(zero.try_normalize(), None);
+    assert_eq!(zero.normalize_or_zero(), zero);
+    assert_eq!(Vector2::default().try_normalize(), None);
+    assert_eq!(Vector3::new(1.0, 2.0, 3.0).project(zero), zero);
+    assert_eq!(zero.angle_between(Vector3::new(1.0, 0.0, 0.0)), 0.0);
+    assert_eq!(Vector2::new(1.0, 2.0).project(Vector2::default()), Vector2::default());
+}
+
+#[test]
+fn magnitude_does_not_overflow(){
+    let big = Vector3::new(1e30, 1e30, 1e30);
+    assert!(big.magnitude().is_finite());
+    assert!(close(big.magnitude(), 3.0f32.sqrt() * 1e30, 1e30));
+    assert!(Vector2::new(f32::MAX / 2.0, f32::MAX / 2.0).magnitude().is_finite());
+    assert_eq!(Vector2i::new(3, 4).magnitude_squared(), 25);
+}
+
+#[test]
+fn approx_eq(){
+    let a = Vector3::new(1.0, 2.0, 3.0);
+    assert!(a.approx_eq(a + Vector3::new(0.0005, -0.0005, 0.0), 0.001));
+    assert!(!a.approx_eq(a + Vector3::new(0.0, 0.0, 0.01), 0.001));
+    assert!(Vector2::new(0.1 + 0.2, 1.0).approx_eq(Vector2::new(0.3, 1.0), f32::EPSILON));
+}
+
+#[test]
+fn component_wise(){
+    let (a, b) = (Vector3i::new(1, -5, 3), Vector3i::new(-2, 4, 3));
+    assert_eq!(a.min(b), Vector3i::new(-2, -5, 3));
+    assert_eq!(a.max(b), Vector3i::new(1, 4, 3));
+    assert_eq!(a.abs(), Vector3i::new(1, 5, 3));
+    assert_eq!(-a, Vector3i::new(-1, 5, -3));
+    assert_eq!(Vector2::new(-3.0, 9.0).clamp(Vector2::new(0.0, 0.0), Vector2::new(5.0, 5.0)), Vector2::new(0.0, 5.0));
+}
+
+#[test]
+fn indexing(){
+    let mut v = Vector3::new(1.0, 2.0, 3.0);
+    assert_eq!((v[0], v[1], v[2]), (1.0, 2.0, 3.0));
+    v[2] = 7.0;
+    assert_eq!(v.z, 7.0);
+    let mut w = Vector2u::new(4, 5);
+    w[0] += 1;
+    assert_eq!(w, Vector2u::new(5, 5));
+    assert_eq!(format!("{:?}", w), "Vec2 { x: 5, y: 5 }");
+}
+
+#[test]
+#[should_panic]
+fn indexing_out_of_bounds(){
+    let v = Vector2::new(1.0, 2.0);
+    let _ = v[2];
+}
+
+#[test]
+fn cast_keeps_precision(){
+    // Past 2^24 these would not survive a trip through f32
+    let v = Vec2::<i64>::new(16_777_217, 123_456_789_012);
+    assert_eq!(v.cast::<i64>(), v);
+    assert_eq!(v.cast::<f64>(), Vector2d::new(16_777_217.0, 123_456_789_012.0));
+    assert_eq!(v.cast::<f64>().cast::<i64>(), v);
+
+    let d = Vector2d::new(0.1, 1e300);
+    assert_eq!(d.cast::<f64>(), d);
+    assert_eq!(d.cast::<f32>(), Vector2::new(0.1, f32::INFINITY));
+    assert_eq!(d.cast::<i32>(), Vector2i::new(0, i32::MAX));
+
+    let big = Vec3::<u64>::new(u64::MAX, 1 << 53, (1 << 53) + 1);
+    assert_eq!(big.cast::<u64>(), big);
+    assert_eq!(big.cast::<i64>(), Vec3::new(-1, 1 << 53, (1 << 53) + 1));
+
+    // f32 can't hold 2^24 + 1, it rounds the same way `as` does
+    assert_eq!(Vector2i::new(16_777_217, 0).to_vector(), Vector2::new(16_777_217_i32 as f32, 0.0));
+    assert_eq!(Vector2i::new(-7, 300).cast::<u8>(), Vec2::new(249, 44));
+}
+
+#[test]
+fn manhattan_saturates(){
+    let (min, max) = (Vector2i::new(i32::MIN, i32::MIN), Vector2i::new(i32::MAX, i32::MAX));
+    assert_eq!(min.manhattan(max), u32::MAX);
+    assert_eq!(min.chebyshev(max), u32::MAX);
+    assert_eq!(Vector2i::new(i32::MIN, 0).manhattan(Vector2i::new(i32::MAX, 1)), u32::MAX);
+
+    let (min, max) = (Vec3::<i8>::new(-128, -128, 0), Vec3::<i8>::new(127, 0, 100));
+    assert_eq!(min.manhattan(max), u8::MAX);
+    assert_eq!(Vec3::<i8>::new(-100, 0, 0).manhattan(Vec3::new(100, 50, 5)), 255);
+    assert_eq!(Vec3::<i8>::new(-50, 0, 0).manhattan(Vec3::new(50, 50, 5)), 155);
+}